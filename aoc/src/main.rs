@@ -0,0 +1,398 @@
+use std::{
+    error::Error,
+    fs::{self, File},
+    io::{self, BufRead, BufReader, Write},
+    path::PathBuf,
+    process,
+};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use common::{Answer, Solution};
+use day07::{report::Report, rules::Rules};
+use day08::{export, part1};
+use day10::{render::Picture, validate::validate};
+
+#[derive(Debug, Parser)]
+#[command(name = "aoc", about = "Advent of Code 2023 runner")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// List every day with a solution
+    List,
+    /// Run the solution for a single day
+    Run {
+        /// Day to run (1-13)
+        #[arg(short, long)]
+        day: u8,
+        /// Part to run, both parts are run when omitted
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Puzzle input, defaults to dayXX/input.txt
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+        /// Read the input a line at a time instead of all at once, for
+        /// inputs too big to fit in memory
+        #[arg(long)]
+        stream: bool,
+    },
+    /// Explain how every Camel Cards hand of day 7 was ranked
+    Hands {
+        /// Part whose rules are used, 2 makes J a joker
+        #[arg(short, long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+        /// Print the report as JSON instead of a table
+        #[arg(long)]
+        json: bool,
+        /// Puzzle input, defaults to day07/input.txt
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+    },
+    /// Export the day 8 node network as a graph
+    Export {
+        #[arg(short, long, value_enum, default_value_t = Format::Dot)]
+        format: Format,
+        /// Highlight the path part one walks from AAA to ZZZ, DOT only
+        #[arg(long)]
+        path: bool,
+        /// Puzzle input, defaults to day08/input.txt
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+        /// File to write to, printed when omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Draw the day 10 pipe loop with inside and outside tiles shaded
+    Render {
+        #[arg(short, long, value_enum, default_value_t = View::Text)]
+        format: View,
+        /// Pixels per tile for SVG and PPM
+        #[arg(short, long, default_value_t = 6)]
+        scale: usize,
+        /// List every problem with the maze instead of drawing it
+        #[arg(long)]
+        check: bool,
+        /// Puzzle input, defaults to day10/input.txt
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+        /// File to write to, printed when omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// List concrete arrangements of day 12 spring records next to the record
+    Springs {
+        /// Only show the record on this line
+        #[arg(short, long)]
+        line: Option<usize>,
+        /// Most arrangements to show per record
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+        /// Show this many arrangements spread over all of them instead of
+        /// the first ones
+        #[arg(long)]
+        sample: Option<usize>,
+        /// Times to unfold each record, 5 for part two
+        #[arg(long, default_value_t = 1)]
+        unfold: usize,
+        /// Puzzle input, defaults to day12/input.txt
+        #[arg(short, long)]
+        input: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum View {
+    /// Unicode box drawing for the terminal
+    Text,
+    /// Vector image with the loop as a polygon
+    Svg,
+    /// Binary PPM image, handy for very large inputs
+    Ppm,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Format {
+    /// Graphviz with L/R edge labels and start and end nodes filled in
+    Dot,
+    /// Adjacency list of every node
+    Json,
+}
+
+// Every day that has a solution, in calendar order
+fn solutions() -> Vec<Box<dyn Solution>> {
+    vec![
+        Box::new(day01::Day01),
+        Box::new(day02::Day02),
+        Box::new(day03::Day03),
+        Box::new(day04::Day04),
+        Box::new(day05::Day05),
+        Box::new(day06::Day06),
+        Box::new(day07::Day07),
+        Box::new(day08::Day08),
+        Box::new(day09::Day09),
+        Box::new(day10::Day10),
+        Box::new(day11::Day11),
+        Box::new(day12::Day12),
+        Box::new(day13::Day13),
+    ]
+}
+
+// Runs the given part of a day, None if the day or part has no solution
+fn solve(day: u8, part: u8, input: &str) -> Option<Result<Answer, Box<dyn Error>>> {
+    let solution = solutions().into_iter().find(|s| s.day() == day)?;
+    match part {
+        1 => Some(solution.part_one(input)),
+        2 => solution.part_two(input),
+        _ => None,
+    }
+}
+
+// Same as `solve` reading the input from `reader` as it goes, None if the
+// day or part cannot be streamed
+fn solve_stream(
+    day: u8,
+    part: u8,
+    reader: &mut dyn BufRead,
+) -> Option<Result<Answer, Box<dyn Error>>> {
+    let solution = solutions().into_iter().find(|s| s.day() == day)?;
+    match part {
+        1 => solution.stream_part_one(reader),
+        2 => solution.stream_part_two(reader),
+        _ => None,
+    }
+}
+
+fn input_path(day: u8, input: Option<PathBuf>) -> PathBuf {
+    input.unwrap_or_else(|| PathBuf::from(format!("day{:02}/input.txt", day)))
+}
+
+// Reads the input of a day, exiting when it cannot be read
+fn read_input(day: u8, input: Option<PathBuf>) -> (PathBuf, String) {
+    let path = input_path(day, input);
+    let contents = fs::read_to_string(&path).unwrap_or_else(|err| {
+        eprintln!("Unable to read {}: {}", path.display(), err);
+        process::exit(1);
+    });
+    (path, contents)
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::List => {
+            for solution in solutions() {
+                println!("Day {:02}: {}", solution.day(), solution.title());
+            }
+        }
+        Command::Run {
+            day,
+            part,
+            input,
+            stream,
+        } => {
+            let (path, contents) = if stream {
+                (input_path(day, input), String::new())
+            } else {
+                read_input(day, input)
+            };
+
+            let parts = match part {
+                Some(part) => vec![part],
+                None => vec![1, 2],
+            };
+            for part in parts {
+                let answer = if stream {
+                    // Each part reads the file again from the start
+                    let mut reader = File::open(&path).map(BufReader::new).unwrap_or_else(|err| {
+                        eprintln!("Unable to read {}: {}", path.display(), err);
+                        process::exit(1);
+                    });
+                    solve_stream(day, part, &mut reader)
+                } else {
+                    solve(day, part, &contents)
+                };
+                match answer {
+                    Some(Ok(answer)) => println!("Day {} Part {}: {}", day, part, answer),
+                    Some(Err(err)) => {
+                        eprintln!(
+                            "Day {} part {} failed: {}: {}",
+                            day,
+                            part,
+                            path.display(),
+                            err
+                        );
+                        process::exit(1);
+                    }
+                    None if stream => {
+                        eprintln!("Day {} part {} cannot be streamed", day, part);
+                        process::exit(1);
+                    }
+                    None => {
+                        eprintln!("Day {} part {} has no solution", day, part);
+                        process::exit(1);
+                    }
+                }
+            }
+        }
+        Command::Hands { part, json, input } => {
+            let (path, contents) = read_input(7, input);
+            let rules = if part == 2 {
+                Rules::jokers()
+            } else {
+                Rules::standard()
+            };
+            match Report::new(&rules, &contents) {
+                Ok(report) if json => println!("{}", report.to_json()),
+                Ok(report) => print!("{}", report),
+                Err(err) => {
+                    eprintln!("Day 7 part {} failed: {}: {}", part, path.display(), err);
+                    process::exit(1);
+                }
+            }
+        }
+        Command::Export {
+            format,
+            path: show_path,
+            input,
+            output,
+        } => {
+            let (path, contents) = read_input(8, input);
+            let lines: Vec<&str> = contents.lines().collect();
+            let (directions, nodes) = part1::process_input(lines).unwrap_or_else(|err| {
+                eprintln!("Day 8 export failed: {}: {}", path.display(), err);
+                process::exit(1);
+            });
+            let graph = match format {
+                Format::Dot if show_path => {
+                    let walked = part1::walk(&directions, &nodes).unwrap_or_else(|err| {
+                        eprintln!("Day 8 export failed: {}: {}", path.display(), err);
+                        process::exit(1);
+                    });
+                    export::to_dot(&nodes, &directions, &walked)
+                }
+                Format::Dot => export::to_dot(&nodes, &directions, &[]),
+                Format::Json => export::to_json(&nodes),
+            };
+            match output {
+                Some(output) => fs::write(&output, graph).unwrap_or_else(|err| {
+                    eprintln!("Unable to write {}: {}", output.display(), err);
+                    process::exit(1);
+                }),
+                None => print!("{}", graph),
+            }
+        }
+        Command::Render {
+            format,
+            scale,
+            check,
+            input,
+            output,
+        } => {
+            let (path, contents) = read_input(10, input);
+            let grid = day10::part1::process_input(&contents).unwrap_or_else(|err| {
+                eprintln!("Day 10 render failed: {}: {}", path.display(), err);
+                process::exit(1);
+            });
+            if check {
+                let problems = validate(&grid);
+                for problem in &problems {
+                    println!("{}: {}", path.display(), problem);
+                }
+                if !problems.is_empty() {
+                    process::exit(1);
+                }
+                println!("{}: no problems found", path.display());
+                return;
+            }
+            let picture = Picture::new(&grid).unwrap_or_else(|err| {
+                eprintln!("Day 10 render failed: {}: {}", path.display(), err);
+                process::exit(1);
+            });
+            let bytes = match format {
+                View::Text => picture.to_text().into_bytes(),
+                View::Svg => picture.to_svg(scale).into_bytes(),
+                View::Ppm => picture.to_ppm(scale),
+            };
+            let written = match &output {
+                Some(output) => fs::write(output, bytes),
+                None => io::stdout().write_all(&bytes),
+            };
+            if let Err(err) = written {
+                let target = output.map_or(String::from("stdout"), |o| o.display().to_string());
+                eprintln!("Unable to write {}: {}", target, err);
+                process::exit(1);
+            }
+        }
+        Command::Springs {
+            line,
+            limit,
+            sample,
+            unfold,
+            input,
+        } => {
+            let (path, contents) = read_input(12, input);
+            let records =
+                day12::part1::process_input(contents.lines().collect()).unwrap_or_else(|err| {
+                    eprintln!("Day 12 springs failed: {}: {}", path.display(), err);
+                    process::exit(1);
+                });
+            for (index, record) in records.iter().enumerate() {
+                if line.is_some_and(|line| line != index + 1) {
+                    continue;
+                }
+                let record = record.unfold(unfold);
+                println!(
+                    "line {}: {} ({} arrangements)",
+                    index + 1,
+                    record,
+                    record.arrangements()
+                );
+                let arrangements: Vec<String> = match sample {
+                    Some(count) => record.sample_arrangements(count).collect(),
+                    None => record.iter_arrangements().take(limit).collect(),
+                };
+                for arrangement in arrangements {
+                    println!("  {}", record.diff(&arrangement));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        let input = "32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483";
+        assert_eq!(solve(7, 1, input).unwrap().unwrap(), Answer::Unsigned(6440));
+        assert_eq!(solve(7, 2, input).unwrap().unwrap(), Answer::Unsigned(5905));
+        assert!(solve(7, 3, input).is_none());
+        assert!(solve(26, 1, input).is_none());
+    }
+
+    #[test]
+    fn test_solve_stream() {
+        let input = "0 3 6 9 12 15\n1 3 6 10 15 21\n10 13 16 21 30 45\n";
+        assert_eq!(
+            solve_stream(9, 1, &mut input.as_bytes()).unwrap().unwrap(),
+            Answer::Signed(114)
+        );
+        assert!(solve_stream(13, 1, &mut input.as_bytes()).is_none());
+    }
+
+    #[test]
+    fn test_solve_error() {
+        let err = solve(7, 1, "32T3K 765\nT55J5 68x").unwrap().unwrap_err();
+        assert_eq!(err.to_string(), "line 2, column 7: invalid number \"68x\"");
+    }
+}
@@ -0,0 +1,101 @@
+use std::{error::Error, fmt, io::BufRead};
+
+pub mod parse;
+pub mod read;
+
+// The result of solving a single part of a day
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Signed(i128),
+    Unsigned(u128),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Signed(value) => write!(f, "{}", value),
+            Answer::Unsigned(value) => write!(f, "{}", value),
+            Answer::Text(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+macro_rules! impl_from_unsigned {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(value: $t) -> Self {
+                Answer::Unsigned(value as u128)
+            }
+        })*
+    };
+}
+
+macro_rules! impl_from_signed {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(value: $t) -> Self {
+                Answer::Signed(value as i128)
+            }
+        })*
+    };
+}
+
+impl_from_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_from_signed!(i8, i16, i32, i64, i128, isize);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(String::from(value))
+    }
+}
+
+// Implemented by every day so the solvers can be used generically
+pub trait Solution {
+    // Day of the advent calendar, 1 through 25
+    fn day(&self) -> u8;
+
+    // Puzzle title as shown on the advent of code site
+    fn title(&self) -> &'static str;
+
+    fn part_one(&self, input: &str) -> Result<Answer, Box<dyn Error>>;
+
+    // None when the day does not have a second part yet
+    fn part_two(&self, _input: &str) -> Option<Result<Answer, Box<dyn Error>>> {
+        None
+    }
+
+    // Same as the parts above but reading the input a line at a time, None
+    // when the day needs the whole input at once
+    fn stream_part_one(&self, _reader: &mut dyn BufRead) -> Option<Result<Answer, Box<dyn Error>>> {
+        None
+    }
+
+    fn stream_part_two(&self, _reader: &mut dyn BufRead) -> Option<Result<Answer, Box<dyn Error>>> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answer_display() {
+        assert_eq!(Answer::from(6440_usize).to_string(), "6440");
+        assert_eq!(Answer::from(-2_i64).to_string(), "-2");
+        assert_eq!(Answer::from("abc").to_string(), "abc");
+    }
+
+    #[test]
+    fn test_answer_from() {
+        assert_eq!(Answer::from(13_u32), Answer::Unsigned(13));
+        assert_eq!(Answer::from(46_i64), Answer::Signed(46));
+    }
+}
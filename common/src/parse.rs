@@ -0,0 +1,227 @@
+use std::{error::Error, fmt, str::FromStr};
+
+// Errors produced while parsing puzzle input. Lines and columns are 1-based
+// so they match what an editor shows for the input file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    // A token that should have been a number
+    InvalidNumber {
+        line: usize,
+        column: usize,
+        token: String,
+    },
+    // A token that is not allowed at this position
+    UnexpectedToken {
+        line: usize,
+        column: usize,
+        token: String,
+        expected: &'static str,
+    },
+    // The line ended before an expected token was found
+    MissingToken {
+        line: usize,
+        column: usize,
+        expected: &'static str,
+    },
+    // The input did not contain anything to parse
+    EmptyInput,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::InvalidNumber {
+                line,
+                column,
+                token,
+            } => write!(
+                f,
+                "line {}, column {}: invalid number {:?}",
+                line, column, token
+            ),
+            ParseError::UnexpectedToken {
+                line,
+                column,
+                token,
+                expected,
+            } => write!(
+                f,
+                "line {}, column {}: expected {} but found {:?}",
+                line, column, expected, token
+            ),
+            ParseError::MissingToken {
+                line,
+                column,
+                expected,
+            } => write!(
+                f,
+                "line {}, column {}: expected {} but the line ended",
+                line, column, expected
+            ),
+            ParseError::EmptyInput => write!(f, "input is empty"),
+        }
+    }
+}
+
+impl ParseError {
+    // Moves the error down by `lines`, for errors from parsing a block that
+    // does not start at the top of the input
+    pub fn offset_lines(self, lines: usize) -> Self {
+        match self {
+            ParseError::InvalidNumber {
+                line,
+                column,
+                token,
+            } => ParseError::InvalidNumber {
+                line: line + lines,
+                column,
+                token,
+            },
+            ParseError::UnexpectedToken {
+                line,
+                column,
+                token,
+                expected,
+            } => ParseError::UnexpectedToken {
+                line: line + lines,
+                column,
+                token,
+                expected,
+            },
+            ParseError::MissingToken {
+                line,
+                column,
+                expected,
+            } => ParseError::MissingToken {
+                line: line + lines,
+                column,
+                expected,
+            },
+            ParseError::EmptyInput => ParseError::EmptyInput,
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+// A single line of puzzle input along with its 1-based line number. Tokens
+// passed to its methods must be slices of `text` so their column can be found.
+#[derive(Debug, Clone, Copy)]
+pub struct Line<'a> {
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    pub fn new(index: usize, text: &'a str) -> Self {
+        Line {
+            number: index + 1,
+            text,
+        }
+    }
+
+    // 1-based column of a token within this line, tokens that are not part
+    // of the line are reported at the start of it
+    pub fn column(&self, token: &str) -> usize {
+        let start = self.text.as_ptr() as usize;
+        let position = token.as_ptr() as usize;
+        if position >= start && position <= start + self.text.len() {
+            self.text[..position - start].chars().count() + 1
+        } else {
+            1
+        }
+    }
+
+    pub fn parse<T: FromStr>(&self, token: &str) -> Result<T, ParseError> {
+        token.parse::<T>().map_err(|_| ParseError::InvalidNumber {
+            line: self.number,
+            column: self.column(token),
+            token: String::from(token),
+        })
+    }
+
+    // Parses every whitespace separated number in `text`
+    pub fn parse_all<T: FromStr>(&self, text: &str) -> Result<Vec<T>, ParseError> {
+        text.split_ascii_whitespace()
+            .map(|token| self.parse(token))
+            .collect()
+    }
+
+    // Splits `text` on the first occurrence of `delimiter`, reporting the end
+    // of the line when it is missing
+    pub fn split_once(
+        &self,
+        text: &'a str,
+        delimiter: &str,
+        expected: &'static str,
+    ) -> Result<(&'a str, &'a str), ParseError> {
+        text.split_once(delimiter)
+            .ok_or_else(|| self.missing(expected))
+    }
+
+    pub fn unexpected(&self, token: &str, expected: &'static str) -> ParseError {
+        ParseError::UnexpectedToken {
+            line: self.number,
+            column: self.column(token),
+            token: String::from(token),
+            expected,
+        }
+    }
+
+    // Same as `unexpected` for a single character at a 0-based char index
+    pub fn unexpected_char(&self, index: usize, c: char, expected: &'static str) -> ParseError {
+        ParseError::UnexpectedToken {
+            line: self.number,
+            column: index + 1,
+            token: c.to_string(),
+            expected,
+        }
+    }
+
+    pub fn missing(&self, expected: &'static str) -> ParseError {
+        ParseError::MissingToken {
+            line: self.number,
+            column: self.text.chars().count() + 1,
+            expected,
+        }
+    }
+}
+
+// Numbers every line of the input, a trailing newline does not produce an
+// empty final line
+pub fn lines(input: &str) -> impl Iterator<Item = Line<'_>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, text)| Line::new(index, text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column() {
+        let line = Line::new(0, "Game 12: 3 blue");
+        let (_, id) = line.text.split_once(' ').unwrap();
+        assert_eq!(line.column(id), 6);
+        assert_eq!(line.column("elsewhere"), 1);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let line = Line::new(2, "Time: 7 1x 30");
+        assert_eq!(
+            line.parse_all::<u64>(&line.text[5..]),
+            Err(ParseError::InvalidNumber {
+                line: 3,
+                column: 9,
+                token: String::from("1x"),
+            })
+        );
+        assert_eq!(
+            line.missing("a distance").to_string(),
+            "line 3, column 14: expected a distance but the line ended"
+        );
+    }
+}
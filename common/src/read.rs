@@ -0,0 +1,101 @@
+use crate::parse::{Line, ParseError};
+use std::{
+    error::Error,
+    fmt,
+    io::{self, BufRead},
+};
+
+// Errors from reading input a line at a time, either the reader failed or
+// the line was not valid for the day
+#[derive(Debug)]
+pub enum ReadError<E = ParseError> {
+    Io(io::Error),
+    Invalid(E),
+}
+
+impl<E: fmt::Display> fmt::Display for ReadError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadError::Io(err) => write!(f, "unable to read input: {}", err),
+            ReadError::Invalid(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl<E: Error + 'static> Error for ReadError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReadError::Io(err) => Some(err),
+            ReadError::Invalid(err) => Some(err),
+        }
+    }
+}
+
+impl<E> From<io::Error> for ReadError<E> {
+    fn from(err: io::Error) -> Self {
+        ReadError::Io(err)
+    }
+}
+
+// Folds `f` over every line of `reader` as it is read. A single buffer is
+// reused for every line so memory does not grow with the size of the input.
+// Lines are numbered like `parse::lines`, a trailing newline does not
+// produce an empty final line and \r\n endings are stripped.
+pub fn fold_lines<R, T, E, F>(mut reader: R, init: T, mut f: F) -> Result<T, ReadError<E>>
+where
+    R: BufRead,
+    F: FnMut(T, Line<'_>) -> Result<T, E>,
+{
+    let mut buffer = String::new();
+    let mut accumulator = init;
+    let mut index = 0;
+    loop {
+        buffer.clear();
+        if reader.read_line(&mut buffer)? == 0 {
+            return Ok(accumulator);
+        }
+        let text = match buffer.strip_suffix('\n') {
+            Some(text) => text.strip_suffix('\r').unwrap_or(text),
+            None => &buffer,
+        };
+        accumulator = f(accumulator, Line::new(index, text)).map_err(ReadError::Invalid)?;
+        index += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fold_lines() {
+        let lines = fold_lines("a\r\n\nbc\n".as_bytes(), Vec::new(), |mut lines, line| {
+            lines.push((line.number, String::from(line.text)));
+            Ok::<_, ParseError>(lines)
+        })
+        .unwrap();
+        assert_eq!(
+            lines,
+            vec![
+                (1, String::from("a")),
+                (2, String::new()),
+                (3, String::from("bc"))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fold_lines_errors() {
+        let err = fold_lines("1\n2\nx\n4".as_bytes(), 0, |sum, line| {
+            Ok::<_, ParseError>(sum + line.parse::<u32>(line.text)?)
+        })
+        .unwrap_err();
+        assert_eq!(err.to_string(), "line 3, column 1: invalid number \"x\"");
+        // Not UTF-8
+        let err = fold_lines(&[b'1', b'\n', 0xff][..], 0, |sum, line| {
+            Ok::<_, ParseError>(sum + line.text.len())
+        })
+        .unwrap_err();
+        assert!(matches!(err, ReadError::Io(_)));
+    }
+}
@@ -0,0 +1,134 @@
+use common::{
+    read::{fold_lines, ReadError},
+    Answer, Solution,
+};
+use std::{convert::Infallible, error::Error, io::BufRead};
+
+// First and last digit of the line as a two digit number, 0 without digits
+fn calibration_value(line: &str) -> usize {
+    let number_chars: Vec<char> = line.chars().filter(|&c| c.is_ascii_digit()).collect();
+    if number_chars.is_empty() {
+        return 0;
+    }
+    format!(
+        "{}{}",
+        number_chars[0],
+        number_chars[number_chars.len() - 1]
+    )
+    .parse::<usize>()
+    .unwrap()
+}
+
+// Same as `calibration_value` with spelled out digits counting as digits
+fn spelled_calibration_value(line: &str) -> usize {
+    // We need to replace with wordNumberword so the spelling of other
+    // numbers is kept correct. example: eightwo -> 82 but without
+    // this the 't' for eight is consumed when we replace it with 2
+    calibration_value(
+        &line
+            .replace("one", "one1one")
+            .replace("two", "two2two")
+            .replace("three", "three3three")
+            .replace("four", "four4four")
+            .replace("five", "five5five")
+            .replace("six", "six6six")
+            .replace("seven", "7seven")
+            .replace("eight", "eight8eight")
+            .replace("nine", "nine9nine"),
+    )
+}
+
+pub fn part_one(input: &str) -> usize {
+    input.split("\n").map(calibration_value).sum()
+}
+
+pub fn part_two(input: &str) -> usize {
+    input.split("\n").map(spelled_calibration_value).sum()
+}
+
+// Nothing in a line can be invalid so only reading can fail
+pub fn stream_part_one<R: BufRead>(reader: R) -> Result<usize, ReadError<Infallible>> {
+    fold_lines(
+        reader,
+        0,
+        |sum, line| Ok(sum + calibration_value(line.text)),
+    )
+}
+
+pub fn stream_part_two<R: BufRead>(reader: R) -> Result<usize, ReadError<Infallible>> {
+    fold_lines(reader, 0, |sum, line| {
+        Ok(sum + spelled_calibration_value(line.text))
+    })
+}
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    fn day(&self) -> u8 {
+        1
+    }
+
+    fn title(&self) -> &'static str {
+        "Trebuchet?!"
+    }
+
+    fn part_one(&self, input: &str) -> Result<Answer, Box<dyn Error>> {
+        Ok(part_one(input).into())
+    }
+
+    fn part_two(&self, input: &str) -> Option<Result<Answer, Box<dyn Error>>> {
+        Some(Ok(part_two(input).into()))
+    }
+
+    fn stream_part_one(&self, reader: &mut dyn BufRead) -> Option<Result<Answer, Box<dyn Error>>> {
+        Some(
+            stream_part_one(reader)
+                .map(Answer::from)
+                .map_err(Into::into),
+        )
+    }
+
+    fn stream_part_two(&self, reader: &mut dyn BufRead) -> Option<Result<Answer, Box<dyn Error>>> {
+        Some(
+            stream_part_two(reader)
+                .map(Answer::from)
+                .map_err(Into::into),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet";
+
+    #[test]
+    fn part_one_test() {
+        assert_eq!(part_one(INPUT), 142);
+    }
+    const INPUT_TWO: &str = "two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen";
+
+    #[test]
+    fn part_two_test() {
+        assert_eq!(part_two(INPUT_TWO), 281);
+    }
+
+    #[test]
+    fn stream_test() {
+        assert_eq!(stream_part_one(INPUT.as_bytes()).unwrap(), 142);
+        assert_eq!(
+            stream_part_two(INPUT_TWO.replace('\n', "\r\n").as_bytes()).unwrap(),
+            281
+        );
+    }
+}
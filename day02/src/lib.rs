@@ -1,3 +1,10 @@
+use common::{
+    parse::{Line, ParseError},
+    read::{fold_lines, ReadError},
+    Answer, Solution,
+};
+use std::{error::Error, io::BufRead};
+
 pub struct Game {
     id: usize,
     // Each round is a vector of cubes stored as RED, GREEN, BLUE
@@ -9,7 +16,7 @@ pub struct Cube {
     count: usize,
 }
 
-pub fn count_cubes(rounds: &Vec<Vec<Cube>>) -> (usize, usize, usize) {
+pub fn count_cubes(rounds: &[Vec<Cube>]) -> (usize, usize, usize) {
     let mut max_reds = 0;
     let mut max_greens = 0;
     let mut max_blues = 0;
@@ -20,33 +27,36 @@ pub fn count_cubes(rounds: &Vec<Vec<Cube>>) -> (usize, usize, usize) {
                 if max_reds < c.count {
                     max_reds = c.count;
                 }
-            },
+            }
             "green" => {
                 if max_greens < c.count {
                     max_greens = c.count;
                 }
-            },
+            }
             "blue" => {
                 if max_blues < c.count {
                     max_blues = c.count;
                 }
-            },
+            }
             _ => unreachable!(),
         });
     });
 
     (max_reds, max_greens, max_blues)
 }
-pub fn create_cube(cube: &str) -> Cube {
-    let c = cube.split(" ").collect::<Vec<&str>>();
-    Cube {
-        color: String::from(c[1]),
-        count: c[0].parse::<usize>().unwrap(),
+pub fn create_cube(line: &Line, cube: &str) -> Result<Cube, ParseError> {
+    let (count, color) = line.split_once(cube, " ", "a cube color")?;
+    if !["red", "green", "blue"].contains(&color) {
+        return Err(line.unexpected(color, "red, green or blue"));
     }
+    Ok(Cube {
+        color: String::from(color),
+        count: line.parse::<usize>(count)?,
+    })
 }
 // Takes a game and will create a vector of all the rounds of the game
 // Example game: {"3 blue, 4 red","1 red, 2 green, 6 blue","2 green"}
-pub fn process_game(game: Vec<&str>) -> Vec<Vec<Cube>> {
+pub fn process_game(line: &Line, game: Vec<&str>) -> Result<Vec<Vec<Cube>>, ParseError> {
     game.iter()
         .map(|&round| {
             //[3 blue, 4 red|1 red, 2 green, 6 blue|2 green]
@@ -54,62 +64,119 @@ pub fn process_game(game: Vec<&str>) -> Vec<Vec<Cube>> {
                 .split(", ")
                 .collect::<Vec<&str>>()
                 .iter()
-                .map(|&r| create_cube(r))
+                .map(|&r| create_cube(line, r))
                 .collect()
         })
-        .collect::<Vec<Vec<Cube>>>()
+        .collect::<Result<Vec<Vec<Cube>>, ParseError>>()
+}
+
+pub fn process_line(line: &Line) -> Result<Game, ParseError> {
+    let (game_id, rounds) = line.split_once(line.text, ": ", "a game")?;
+    // Get game_id
+    let (_, game_id) = line.split_once(game_id, " ", "a game id")?;
+    Ok(Game {
+        id: line.parse::<usize>(game_id)?,
+        rounds: process_game(line, rounds.split("; ").collect())?,
+    })
 }
 
-pub fn process_games(games: Vec<&str>) -> Vec<Game> {
+pub fn process_games(games: Vec<&str>) -> Result<Vec<Game>, ParseError> {
     games
         .iter()
-        .map(|&game| {
-            let split: Vec<&str> = game.split(": ").collect();
-            // Get game_id
-            let game_id: usize = split[0].split(" ").collect::<Vec<&str>>()[1]
-                .parse::<usize>()
-                .unwrap();
-            split[0].split(" ").collect::<Vec<&str>>()[1]
-                .parse::<usize>()
-                .unwrap();
-            Game {
-                id: game_id,
-                rounds: process_game(split[1].split("; ").collect()),
-            }
-        })
-        .collect::<Vec<Game>>()
+        .enumerate()
+        .map(|(index, &game)| process_line(&Line::new(index, game)))
+        .collect::<Result<Vec<Game>, ParseError>>()
+}
+
+// Whether the game could have been played with 12 red, 13 green and 14
+// blue cubes
+fn is_possible(game: &Game) -> bool {
+    let count = count_cubes(&game.rounds);
+    count.0 <= 12 && count.1 <= 13 && count.2 <= 14
+}
+
+// Product of the fewest cubes of each color the game needs
+fn power(game: &Game) -> usize {
+    let count = count_cubes(&game.rounds);
+    count.0 * count.1 * count.2
 }
 
-pub fn part_one(input: &str) -> usize {
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
     let mut lines: Vec<&str> = input.split("\n").collect();
-    if lines[lines.len() - 1] == "" {
+    if lines[lines.len() - 1].is_empty() {
         lines.pop();
     }
-    let processed_games = process_games(lines);
-    processed_games
+    let processed_games = process_games(lines)?;
+    Ok(processed_games
         .iter()
-        .filter(|&g| {
-            let count = count_cubes(&g.rounds);
-            // rounds is a vector of cubes
-            count.0 <= 12 && count.1 <= 13 && count.2 <= 14
-        })
+        .filter(|&g| is_possible(g))
         .map(|g| g.id)
-        .sum::<usize>()
+        .sum::<usize>())
 }
 
-pub fn part_two(input: &str) -> usize {
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
     let mut lines: Vec<&str> = input.split("\n").collect();
-    if lines[lines.len() - 1] == "" {
+    if lines[lines.len() - 1].is_empty() {
         lines.pop();
     }
-    let processed_games = process_games(lines);
-    processed_games
-        .iter()
-        .map(|g| {
-            let count = count_cubes(&g.rounds);
-            count.0 * count.1 * count.2
+    let processed_games = process_games(lines)?;
+    Ok(processed_games.iter().map(power).sum::<usize>())
+}
+
+// Same as the parts above with one game in memory at a time
+pub fn stream_part_one<R: BufRead>(reader: R) -> Result<usize, ReadError> {
+    fold_lines(reader, 0, |sum, line| {
+        let game = process_line(&line)?;
+        Ok(if is_possible(&game) {
+            sum + game.id
+        } else {
+            sum
         })
-        .sum::<usize>()
+    })
+}
+
+pub fn stream_part_two<R: BufRead>(reader: R) -> Result<usize, ReadError> {
+    fold_lines(
+        reader,
+        0,
+        |sum, line| Ok(sum + power(&process_line(&line)?)),
+    )
+}
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    fn day(&self) -> u8 {
+        2
+    }
+
+    fn title(&self) -> &'static str {
+        "Cube Conundrum"
+    }
+
+    fn part_one(&self, input: &str) -> Result<Answer, Box<dyn Error>> {
+        part_one(input).map(Answer::from).map_err(Into::into)
+    }
+
+    fn part_two(&self, input: &str) -> Option<Result<Answer, Box<dyn Error>>> {
+        Some(part_two(input).map(Answer::from).map_err(Into::into))
+    }
+
+    fn stream_part_one(&self, reader: &mut dyn BufRead) -> Option<Result<Answer, Box<dyn Error>>> {
+        Some(
+            stream_part_one(reader)
+                .map(Answer::from)
+                .map_err(Into::into),
+        )
+    }
+
+    fn stream_part_two(&self, reader: &mut dyn BufRead) -> Option<Result<Answer, Box<dyn Error>>> {
+        Some(
+            stream_part_two(reader)
+                .map(Answer::from)
+                .map_err(Into::into),
+        )
+    }
 }
 
 #[cfg(test)]
@@ -156,7 +223,7 @@ mod tests {
             "Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red",
             "Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green",
         ];
-        let processed_games = process_games(games);
+        let processed_games = process_games(games).unwrap();
         assert_eq!(processed_games.len(), 5)
     }
 
@@ -170,7 +237,7 @@ Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
 Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"
             ),
-            8
+            Ok(8)
         );
     }
     #[test]
@@ -183,7 +250,50 @@ Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
 Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"
             ),
-            2286
+            Ok(2286)
+        );
+    }
+
+    #[test]
+    fn stream_test() {
+        let input = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+";
+        assert_eq!(stream_part_one(input.as_bytes()).unwrap(), 8);
+        assert_eq!(stream_part_two(input.as_bytes()).unwrap(), 2286);
+        let err = stream_part_one("Game 1: 3 blue\nGame 2: 3 grey".as_bytes()).unwrap_err();
+        assert!(matches!(
+            err,
+            ReadError::Invalid(ParseError::UnexpectedToken { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn process_games_error_test() {
+        let games = vec![
+            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green",
+            "Game 2: 1 blue, 2 green; 3 grey, 4 blue, 1 red",
+            "Game x: 1 blue",
+        ];
+        assert_eq!(
+            process_games(games).err(),
+            Some(ParseError::UnexpectedToken {
+                line: 2,
+                column: 28,
+                token: String::from("grey"),
+                expected: "red, green or blue",
+            })
+        );
+        assert_eq!(
+            process_games(vec!["Game x: 1 blue"]).err(),
+            Some(ParseError::InvalidNumber {
+                line: 1,
+                column: 6,
+                token: String::from("x"),
+            })
         );
     }
 }
@@ -1,217 +1,156 @@
+use common::{parse::ParseError, Answer, Solution};
+use grid::{Grid, Position};
+use std::error::Error;
+
 #[derive(Debug, Clone)]
 pub struct PartNumber {
     // Value of the part number
     value: usize,
-    // Positions in the matrix that the number is found (row, col)
-    positions: Vec<(usize, usize)>,
+    // Positions in the grid that the number is found (x, y)
+    positions: Vec<Position>,
 }
 
-pub fn process_symbols(symbols: Vec<Vec<char>>) -> Vec<(usize, usize)> {
-    let mut symbols_matches: Vec<(usize, usize)> = Vec::new();
-    for i in 0..symbols.len() {
-        for j in 0..symbols[i].len() {
-            let v = symbols[i][j];
-            if v != '.' && !v.is_digit(10) {
-                symbols_matches.push((i, j));
-            }
-        }
+impl PartNumber {
+    // True when any digit of the number touches the position, diagonals
+    // included
+    fn is_adjacent(&self, grid: &Grid<char>, position: Position) -> bool {
+        self.positions
+            .iter()
+            .any(|&p| grid.neighbors8(p).any(|n| n == position))
     }
-    symbols_matches
 }
 
-pub fn process_symbols_two(symbols: Vec<Vec<char>>) -> Vec<(usize, usize)> {
-    let mut symbols_matches: Vec<(usize, usize)> = Vec::new();
-    for i in 0..symbols.len() {
-        for j in 0..symbols[i].len() {
-            let v = symbols[i][j];
-            if v == '*' {
-                symbols_matches.push((i, j));
-            }
-        }
-    }
-    symbols_matches
+fn is_symbol(c: char) -> bool {
+    c != '.' && !c.is_ascii_digit()
 }
 
-pub fn find_gear_ratio(symbol: (usize, usize), part_numbers: Vec<PartNumber>) -> usize {
-    let mut position_matches: Vec<usize> = vec![];
-    for i in 0..part_numbers.len() {
-        let positions = part_numbers[i].positions.clone();
-        if position_matches.len() > 2 {
-            return 0;
-        }
-        let mut matched_on_part_number = false;
-        for j in 0..positions.len() {
-            if matched_on_part_number {
-                continue;
-            }
-            let position = positions[j];
-            if generate_symbols_matches((position.0, position.1), symbol) {
-                position_matches.push(part_numbers[i].value);
-                matched_on_part_number = true;
-            }
-        }
-    }
+pub fn process_symbols(grid: &Grid<char>) -> Vec<Position> {
+    grid.iter()
+        .filter(|(_, &c)| is_symbol(c))
+        .map(|(position, _)| position)
+        .collect()
+}
+
+pub fn process_symbols_two(grid: &Grid<char>) -> Vec<Position> {
+    grid.iter()
+        .filter(|(_, &c)| c == '*')
+        .map(|(position, _)| position)
+        .collect()
+}
+
+pub fn find_gear_ratio(grid: &Grid<char>, symbol: Position, part_numbers: &[PartNumber]) -> usize {
+    let position_matches: Vec<usize> = part_numbers
+        .iter()
+        .filter(|p| p.is_adjacent(grid, symbol))
+        .map(|p| p.value)
+        .collect();
     if position_matches.len() == 2 {
         return position_matches[0] * position_matches[1];
     }
     0
 }
 
-pub fn generate_symbols_matches(
-    part_position: (usize, usize),
-    symbol_position: (usize, usize),
-) -> bool {
-    // LEFT
-    if part_position.0 > 0
-        && (part_position.0 - 1 == symbol_position.0 && part_position.1 == symbol_position.1)
-    {
-        return true;
-    }
-    // RIGHT
-    if part_position.0 + 1 == symbol_position.0 && part_position.1 == symbol_position.1 {
-        return true;
-    }
-    // UP
-    if part_position.1 + 1 == symbol_position.1 && part_position.0 == symbol_position.0 {
-        return true;
-    }
-    // DOWN
-    if part_position.1 > 0
-        && (part_position.1 - 1 == symbol_position.1 && part_position.0 == symbol_position.0)
-    {
-        return true;
-    }
-    // LEFT-UP
-    if part_position.0 > 0
-        && (part_position.1 + 1 == symbol_position.1 && part_position.0 - 1 == symbol_position.0)
-    {
-        return true;
-    }
-    // RIGHT-UP
-    if part_position.1 + 1 == symbol_position.1 && part_position.0 + 1 == symbol_position.0 {
-        return true;
-    }
-    // RIGHT-DOWN
-    if part_position.1 > 0
-        && (part_position.1 - 1 == symbol_position.1 && part_position.0 + 1 == symbol_position.0)
-    {
-        return true;
-    }
-    // LEFT-DOWN
-    if part_position.0 > 0
-        && part_position.1 > 0
-        && (part_position.1 - 1 == symbol_position.1 && part_position.0 - 1 == symbol_position.0)
-    {
-        return true;
-    }
-    false
-}
-
-pub fn validate_part_number(part_number: &PartNumber, symbol_matches: Vec<(usize, usize)>) -> bool {
+pub fn validate_part_number(grid: &Grid<char>, part_number: &PartNumber) -> bool {
     part_number
         .positions
         .iter()
-        .map(|&p| {
-            symbol_matches
-                .iter()
-                .filter(|&s| generate_symbols_matches(p, *s))
-                .count()
-        })
-        .sum::<usize>()
-        > 0
+        .any(|&p| grid.neighbors8(p).any(|n| is_symbol(grid[n])))
 }
 
-pub fn generate_part_number_positions(
-    row: usize,
-    col: usize,
-    part_number: &str,
-) -> Vec<(usize, usize)> {
-    // 467 -> [(0,0),(0,1),(0,2)]
-    let mut col_position = col;
-    part_number
-        .chars()
-        .collect::<Vec<char>>()
-        .iter()
-        .map(|&_c| {
-            let curr_position = col_position;
-            col_position += 1;
-            (row, curr_position)
-        })
+pub fn generate_part_number_positions(row: usize, col: usize, part_number: &str) -> Vec<Position> {
+    // 467 -> [(0,0),(1,0),(2,0)]
+    (col..col + part_number.chars().count())
+        .map(|x| (x, row))
         .collect()
 }
 
-pub fn get_part_numbers(lines: Vec<&str>) -> Vec<PartNumber> {
+// Part numbers are only ever digits so the parse can only fail when the number
+// is too large for a usize
+fn create_part_number(row: usize, col: usize, part_number: &str) -> Result<PartNumber, ParseError> {
+    let value = part_number
+        .parse::<usize>()
+        .map_err(|_| ParseError::InvalidNumber {
+            line: row + 1,
+            column: col + 1,
+            token: String::from(part_number),
+        })?;
+    Ok(PartNumber {
+        value,
+        positions: generate_part_number_positions(row, col, part_number),
+    })
+}
+
+pub fn get_part_numbers(grid: &Grid<char>) -> Result<Vec<PartNumber>, ParseError> {
     let mut part_numbers: Vec<PartNumber> = Vec::new();
     // Iterate over the rows
-    for i in 0..lines.len() {
-        let row = lines[i].chars().collect::<Vec<char>>();
+    for (i, row) in grid.rows().enumerate() {
         let mut rolling_part_number = String::from("");
         let mut starting_position = 0;
-        for j in 0..row.len() {
-            let value = row[j];
-            if value.is_digit(10) {
+        for (j, &value) in row.iter().enumerate() {
+            if value.is_ascii_digit() {
                 if rolling_part_number.is_empty() {
                     starting_position = j;
                 }
                 rolling_part_number.push(value);
             }
-            if !value.is_digit(10) {
-                if !rolling_part_number.is_empty() {
-                    part_numbers.push(PartNumber {
-                        value: rolling_part_number.parse::<usize>().unwrap(),
-                        positions: generate_part_number_positions(
-                            i,
-                            starting_position,
-                            rolling_part_number.as_str(),
-                        ),
-                    });
-                    rolling_part_number.clear();
-                }
+            if !value.is_ascii_digit() && !rolling_part_number.is_empty() {
+                part_numbers.push(create_part_number(
+                    i,
+                    starting_position,
+                    &rolling_part_number,
+                )?);
+                rolling_part_number.clear();
             }
         }
         if !rolling_part_number.is_empty() {
-            part_numbers.push(PartNumber {
-                value: rolling_part_number.parse::<usize>().unwrap(),
-                positions: generate_part_number_positions(
-                    i,
-                    starting_position,
-                    rolling_part_number.as_str(),
-                ),
-            });
+            part_numbers.push(create_part_number(
+                i,
+                starting_position,
+                &rolling_part_number,
+            )?);
             rolling_part_number.clear();
         }
     }
-    part_numbers
+    Ok(part_numbers)
 }
 
-pub fn part_one(input: &str) -> usize {
-    let mut lines = input.split("\n").collect::<Vec<&str>>();
-    if lines[lines.len() - 1] == "" {
-        lines.pop();
-    }
-    let symbols: Vec<(usize, usize)> =
-        process_symbols(lines.iter().map(|&l| l.chars().collect()).collect());
-    let part_numbers = get_part_numbers(lines);
-    part_numbers
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    let grid = Grid::from_chars(input)?;
+    let part_numbers = get_part_numbers(&grid)?;
+    Ok(part_numbers
         .iter()
-        .filter(|&p| return validate_part_number(p, symbols.clone()))
+        .filter(|&p| validate_part_number(&grid, p))
         .map(|p| p.value)
-        .sum::<usize>()
+        .sum::<usize>())
 }
 
-pub fn part_two(input: &str) -> usize {
-    let mut lines = input.split("\n").collect::<Vec<&str>>();
-    if lines[lines.len() - 1] == "" {
-        lines.pop();
-    }
-    let symbols: Vec<(usize, usize)> =
-        process_symbols_two(lines.iter().map(|&l| l.chars().collect()).collect());
-    let part_numbers = get_part_numbers(lines);
-    // Create a map of all the possible part number positions
-    symbols
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    let grid = Grid::from_chars(input)?;
+    let part_numbers = get_part_numbers(&grid)?;
+    Ok(process_symbols_two(&grid)
         .iter()
-        .map(|&s| find_gear_ratio(s, part_numbers.clone()))
-        .sum::<usize>()
+        .map(|&s| find_gear_ratio(&grid, s, &part_numbers))
+        .sum::<usize>())
+}
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    fn day(&self) -> u8 {
+        3
+    }
+
+    fn title(&self) -> &'static str {
+        "Gear Ratios"
+    }
+
+    fn part_one(&self, input: &str) -> Result<Answer, Box<dyn Error>> {
+        part_one(input).map(Answer::from).map_err(Into::into)
+    }
+
+    fn part_two(&self, input: &str) -> Option<Result<Answer, Box<dyn Error>>> {
+        Some(part_two(input).map(Answer::from).map_err(Into::into))
+    }
 }
 
 #[cfg(test)]
@@ -222,17 +161,19 @@ mod tests {
     fn test_generate_part_number_positions() {
         assert_eq!(
             generate_part_number_positions(0, 0, "123"),
-            vec![(0, 0), (0, 1), (0, 2)]
+            vec![(0, 0), (1, 0), (2, 0)]
         );
     }
 
     #[test]
     fn test_process_input() {
-        let result = get_part_numbers("123...617*...#...*123...*10*".split("\n").collect());
+        let grid = Grid::from_chars("123...617*...#...*123...*10*").unwrap();
+        let result = get_part_numbers(&grid).unwrap();
         assert_eq!(
             result.iter().map(|p| { p.value }).collect::<Vec<usize>>(),
             vec![123, 617, 123, 10]
-        )
+        );
+        assert_eq!(process_symbols(&grid).len(), 5);
     }
 
     #[test]
@@ -247,7 +188,7 @@ mod tests {
 ......755.
 ...$.*....
 .664.598..";
-        assert_eq!(part_one(input), 4361);
+        assert_eq!(part_one(input), Ok(4361));
     }
 
     #[test]
@@ -262,6 +203,6 @@ mod tests {
 ......755.
 ...$.*....
 .664.598..";
-        assert_eq!(part_two(input), 467835);
+        assert_eq!(part_two(input), Ok(467835));
     }
 }
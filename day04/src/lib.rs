@@ -1,4 +1,13 @@
-use std::collections::HashSet;
+use common::{
+    parse::{Line, ParseError},
+    read::{fold_lines, ReadError},
+    Answer, Solution,
+};
+use std::{
+    collections::{HashSet, VecDeque},
+    error::Error,
+    io::BufRead,
+};
 
 #[derive(Debug)]
 pub struct Card {
@@ -10,58 +19,49 @@ impl Card {
     fn count_matches(&self) -> u32 {
         self.winning_nums.intersection(&self.card_nums).count() as u32
     }
+
+    fn points(&self) -> u32 {
+        let count = self.count_matches();
+        if count == 0 {
+            return 0;
+        }
+        2_u32.pow(count - 1)
+    }
+}
+
+pub fn process_card(line: &Line) -> Result<Card, ParseError> {
+    let (_, numbers) = line.split_once(line.text, ":", "a card")?;
+    let (winning_nums, card_nums) = line.split_once(numbers, "|", "card numbers")?;
+    Ok(Card {
+        winning_nums: line.parse_all::<u32>(winning_nums)?.into_iter().collect(),
+        card_nums: line.parse_all::<u32>(card_nums)?.into_iter().collect(),
+    })
 }
 
-pub fn process_cards(lines: Vec<&str>) -> Vec<Card> {
+pub fn process_cards(lines: Vec<&str>) -> Result<Vec<Card>, ParseError> {
     lines
         .iter()
-        .map(|&card| {
-            let c = card.split(":").collect::<Vec<&str>>();
-            let winning_nums = c[1].split("|").collect::<Vec<&str>>()[0]
-                .split_ascii_whitespace()
-                .collect::<Vec<&str>>()
-                .iter()
-                .map(|&s| s.parse::<u32>().unwrap())
-                .collect::<HashSet<u32>>();
-            let card_nums = c[1].split("|").collect::<Vec<&str>>()[1]
-                .split_ascii_whitespace()
-                .collect::<Vec<&str>>()
-                .iter()
-                .map(|&s| s.parse::<u32>().unwrap())
-                .collect::<HashSet<u32>>();
-            Card {
-                winning_nums,
-                card_nums,
-            }
-        })
-        .collect::<Vec<Card>>()
+        .enumerate()
+        .map(|(index, &card)| process_card(&Line::new(index, card)))
+        .collect::<Result<Vec<Card>, ParseError>>()
 }
 
-pub fn part_one(input: &str) -> u32 {
+pub fn part_one(input: &str) -> Result<u32, ParseError> {
     let mut lines = input.split("\n").collect::<Vec<&str>>();
-    if lines[lines.len() - 1] == "" {
+    if lines[lines.len() - 1].is_empty() {
         lines.pop();
     }
 
-    process_cards(lines)
-        .iter()
-        .map(|c| {
-            let count = c.count_matches();
-            if count == 0 {
-                return 0;
-            }
-            2_u32.pow(count - 1)
-        })
-        .sum::<u32>()
+    Ok(process_cards(lines)?.iter().map(Card::points).sum::<u32>())
 }
 
-pub fn part_two(input: &str) -> u32 {
+pub fn part_two(input: &str) -> Result<u32, ParseError> {
     let mut lines = input.split("\n").collect::<Vec<&str>>();
-    if lines[lines.len() - 1] == "" {
+    if lines[lines.len() - 1].is_empty() {
         lines.pop();
     }
 
-    let cards = process_cards(lines);
+    let cards = process_cards(lines)?;
     // Keep track of the games and the number of times we have played them
     let mut games = vec![0; cards.len()];
 
@@ -72,7 +72,7 @@ pub fn part_two(input: &str) -> u32 {
         let win_count = cards[i].count_matches();
         // For each win we want to go through the next x cards where x is the
         // number of wins
-        for win in 1..win_count+1{
+        for win in 1..win_count + 1 {
             // Need to add i + win to get the correct game index
             let index = i + usize::try_from(win).unwrap();
             // Update the game with the count of the current game since it will
@@ -81,7 +81,70 @@ pub fn part_two(input: &str) -> u32 {
         }
     }
 
-    games.iter().sum::<u32>()
+    Ok(games.iter().sum::<u32>())
+}
+
+pub fn stream_part_one<R: BufRead>(reader: R) -> Result<u32, ReadError> {
+    fold_lines(reader, 0, |sum, line| {
+        Ok(sum + process_card(&line)?.points())
+    })
+}
+
+// Same as part two but only the copies won for the next few cards are kept,
+// never more than the most numbers a card matches
+pub fn stream_part_two<R: BufRead>(reader: R) -> Result<u32, ReadError> {
+    let (total, _) = fold_lines(
+        reader,
+        (0, VecDeque::new()),
+        |(total, mut won): (u32, VecDeque<u32>), line| {
+            let copies = 1 + won.pop_front().unwrap_or(0);
+            let win_count = process_card(&line)?.count_matches() as usize;
+            for next in 0..win_count {
+                match won.get_mut(next) {
+                    Some(count) => *count += copies,
+                    None => won.push_back(copies),
+                }
+            }
+            Ok((total + copies, won))
+        },
+    )?;
+    Ok(total)
+}
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    fn day(&self) -> u8 {
+        4
+    }
+
+    fn title(&self) -> &'static str {
+        "Scratchcards"
+    }
+
+    fn part_one(&self, input: &str) -> Result<Answer, Box<dyn Error>> {
+        part_one(input).map(Answer::from).map_err(Into::into)
+    }
+
+    fn part_two(&self, input: &str) -> Option<Result<Answer, Box<dyn Error>>> {
+        Some(part_two(input).map(Answer::from).map_err(Into::into))
+    }
+
+    fn stream_part_one(&self, reader: &mut dyn BufRead) -> Option<Result<Answer, Box<dyn Error>>> {
+        Some(
+            stream_part_one(reader)
+                .map(Answer::from)
+                .map_err(Into::into),
+        )
+    }
+
+    fn stream_part_two(&self, reader: &mut dyn BufRead) -> Option<Result<Answer, Box<dyn Error>>> {
+        Some(
+            stream_part_two(reader)
+                .map(Answer::from)
+                .map_err(Into::into),
+        )
+    }
 }
 
 #[cfg(test)]
@@ -96,7 +159,7 @@ Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
 Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
-        assert_eq!(part_one(input), 13);
+        assert_eq!(part_one(input), Ok(13));
     }
 
     #[test]
@@ -107,6 +170,35 @@ Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
 Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
 Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
-        assert_eq!(part_two(input), 30);
+        assert_eq!(part_two(input), Ok(30));
+    }
+
+    #[test]
+    fn test_stream() {
+        let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+";
+        assert_eq!(stream_part_one(input.as_bytes()).unwrap(), 13);
+        assert_eq!(stream_part_two(input.as_bytes()).unwrap(), 30);
+    }
+
+    #[test]
+    fn test_process_cards_error() {
+        let input = vec![
+            "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53",
+            "Card 2: 13 32 20 16 61   61 30 68 82 17 32 24 19",
+        ];
+        assert_eq!(
+            process_cards(input).err(),
+            Some(ParseError::MissingToken {
+                line: 2,
+                column: 49,
+                expected: "card numbers",
+            })
+        );
     }
 }
@@ -1,161 +1,292 @@
-use rayon::prelude::*;
-
-pub fn get_seeds(seed_line: &str) -> Vec<usize> {
-    seed_line.split(": ").collect::<Vec<&str>>()[1]
-        .split(" ")
-        .collect::<Vec<&str>>()
-        .iter()
-        .map(|&s| s.parse::<usize>().unwrap())
-        .collect::<Vec<usize>>()
+use common::{
+    parse::{self, Line, ParseError},
+    Answer, Solution,
+};
+use std::{
+    collections::{HashMap, VecDeque},
+    error::Error,
+    fmt,
+};
+
+pub fn get_seeds(seed_line: &Line) -> Result<Vec<usize>, ParseError> {
+    let (_, seeds) = seed_line.split_once(seed_line.text, ": ", "seeds")?;
+    let seeds = seed_line.parse_all::<usize>(seeds)?;
+    if seeds.is_empty() {
+        return Err(seed_line.missing("a seed"));
+    }
+    Ok(seeds)
 }
 
-pub fn get_seeds_part_two(seed_line: &str) -> Vec<(i64, i64)> {
-    seed_line.split(": ").collect::<Vec<&str>>()[1]
-        .split(" ")
-        .collect::<Vec<&str>>()
+// Seed ranges as (start, length)
+pub fn get_seeds_part_two(seed_line: &Line) -> Result<Vec<(usize, usize)>, ParseError> {
+    let seeds = get_seeds(seed_line)?;
+    if seeds.len() % 2 != 0 {
+        return Err(seed_line.missing("a seed range length"));
+    }
+    Ok(seeds
         .chunks(2)
-        .map(|s| (s[0].parse::<i64>().unwrap(), s[1].parse::<i64>().unwrap()))
-        .collect::<Vec<(i64, i64)>>()
+        .map(|s| (s[0], s[1]))
+        .collect::<Vec<(usize, usize)>>())
 }
 
-pub fn process_map_ranges(lines: Vec<&str>) -> Vec<Vec<(usize, usize, usize)>> {
-    let mut seed_to_soil_range = (0, 0);
-    let mut soil_to_fertilizer_range = (0, 0);
-    let mut fertilizer_to_water_range = (0, 0);
-    let mut water_to_light_range = (0, 0);
-    let mut light_to_temperature_range = (0, 0);
-    let mut temperature_to_humidity_range = (0, 0);
-    let mut humidity_to_location_range = (0, lines.len());
-
-    for i in 0..lines.len() {
-        // First line is the seeds line
-        if i == 0 {
-            continue;
-        }
-        match lines[i] {
-            "seed-to-soil map:" => seed_to_soil_range.0 = i + 1,
-            "soil-to-fertilizer map:" => {
-                seed_to_soil_range.1 = i;
-                soil_to_fertilizer_range.0 = i + 1
-            }
-            "fertilizer-to-water map:" => {
-                soil_to_fertilizer_range.1 = i;
-                fertilizer_to_water_range.0 = i + 1
-            }
-            "water-to-light map:" => {
-                fertilizer_to_water_range.1 = i;
-                water_to_light_range.0 = i + 1
-            }
-            "light-to-temperature map:" => {
-                water_to_light_range.1 = i;
-                light_to_temperature_range.0 = i + 1
-            }
-            "temperature-to-humidity map:" => {
-                light_to_temperature_range.1 = i;
-                temperature_to_humidity_range.0 = i + 1
+// Parses a single "destination source length" line of a map
+fn parse_map_line(line: &Line) -> Result<MapLine, ParseError> {
+    match line.parse_all::<usize>(line.text)?[..] {
+        [destination, source, length] => Ok((destination, source, length)),
+        _ => Err(line.unexpected(line.text, "a destination, source and length")),
+    }
+}
+
+// A "destination source length" line of a map
+pub type MapLine = (usize, usize, usize);
+
+// A single "X-to-Y map:" section of the almanac
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CategoryMap {
+    pub source: String,
+    pub destination: String,
+    pub lines: Vec<MapLine>,
+}
+
+// Every map in the almanac, each one is an edge from its source category to
+// its destination category
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Almanac {
+    pub maps: Vec<CategoryMap>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlmanacError {
+    Parse(ParseError),
+    // No chain of maps leads from one category to the other
+    NoConversion { from: String, to: String },
+}
+
+impl fmt::Display for AlmanacError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlmanacError::Parse(err) => write!(f, "{}", err),
+            AlmanacError::NoConversion { from, to } => {
+                write!(f, "no maps convert {} to {}", from, to)
             }
-            "humidity-to-location map:" => {
-                temperature_to_humidity_range.1 = i;
-                humidity_to_location_range.0 = i + 1
+        }
+    }
+}
+
+impl Error for AlmanacError {}
+
+impl From<ParseError> for AlmanacError {
+    fn from(err: ParseError) -> Self {
+        AlmanacError::Parse(err)
+    }
+}
+
+// Parses an "X-to-Y map:" header into (X, Y)
+fn parse_map_header<'a>(line: &Line<'a>) -> Option<Result<(&'a str, &'a str), ParseError>> {
+    let name = line.text.strip_suffix(" map:")?;
+    Some(match name.split_once("-to-") {
+        Some((source, destination)) if !source.is_empty() && !destination.is_empty() => {
+            Ok((source, destination))
+        }
+        _ => Err(line.unexpected(name, "a source-to-destination map name")),
+    })
+}
+
+// Every line after the seeds line is either a map header or a line of the
+// map above it
+pub fn process_almanac(lines: &[Line]) -> Result<Almanac, ParseError> {
+    let mut maps: Vec<CategoryMap> = Vec::new();
+
+    // First line is the seeds line
+    for line in lines.iter().skip(1) {
+        match parse_map_header(line) {
+            Some(header) => {
+                let (source, destination) = header?;
+                maps.push(CategoryMap {
+                    source: String::from(source),
+                    destination: String::from(destination),
+                    lines: Vec::new(),
+                });
             }
-            _ => {}
+            None => match maps.last_mut() {
+                Some(map) => map.lines.push(parse_map_line(line)?),
+                None => return Err(line.unexpected(line.text, "a map header")),
+            },
         }
     }
 
-    let mut seed_to_soil_map_lines: Vec<(usize, usize, usize)> = Vec::new();
+    Ok(Almanac { maps })
+}
 
-    for i in seed_to_soil_range.0..seed_to_soil_range.1 {
-        let parse = lines[i].split(" ").collect::<Vec<&str>>();
-        seed_to_soil_map_lines.push((
-            parse[0].parse::<usize>().unwrap(),
-            parse[1].parse::<usize>().unwrap(),
-            parse[2].parse::<usize>().unwrap(),
-        ))
+impl CategoryMap {
+    // A map that keeps every number as is
+    pub fn identity(category: &str) -> CategoryMap {
+        CategoryMap {
+            source: String::from(category),
+            destination: String::from(category),
+            lines: Vec::new(),
+        }
     }
-    let mut soil_to_fertilizer_map_lines: Vec<(usize, usize, usize)> = Vec::new();
-
-    for i in soil_to_fertilizer_range.0..soil_to_fertilizer_range.1 {
-        let parse = lines[i].split(" ").collect::<Vec<&str>>();
-        soil_to_fertilizer_map_lines.push((
-            parse[0].parse::<usize>().unwrap(),
-            parse[1].parse::<usize>().unwrap(),
-            parse[2].parse::<usize>().unwrap(),
-        ))
+
+    pub fn apply(&self, value: usize) -> usize {
+        map_category(value, &self.lines)
     }
-    let mut fertilizer_to_water_map_lines: Vec<(usize, usize, usize)> = Vec::new();
-
-    for i in fertilizer_to_water_range.0..fertilizer_to_water_range.1 {
-        let parse = lines[i].split(" ").collect::<Vec<&str>>();
-        fertilizer_to_water_map_lines.push((
-            parse[0].parse::<usize>().unwrap(),
-            parse[1].parse::<usize>().unwrap(),
-            parse[2].parse::<usize>().unwrap(),
-        ))
+
+    // Every source number that ends up at `value`, sorted
+    pub fn preimages(&self, value: usize) -> Vec<usize> {
+        let mut sources: Vec<usize> = split_ranges(vec![(0, usize::MAX)], &self.lines)
+            .into_iter()
+            .filter(|&(destination, _, length)| {
+                value >= destination && value - destination < length
+            })
+            .map(|(destination, source, _)| source + (value - destination))
+            .collect();
+        sources.sort();
+        sources
     }
-    let mut water_to_light_map_lines: Vec<(usize, usize, usize)> = Vec::new();
-
-    for i in water_to_light_range.0..water_to_light_range.1 {
-        let parse = lines[i].split(" ").collect::<Vec<&str>>();
-        water_to_light_map_lines.push((
-            parse[0].parse::<usize>().unwrap(),
-            parse[1].parse::<usize>().unwrap(),
-            parse[2].parse::<usize>().unwrap(),
-        ))
+
+    // Whether no two numbers end up at the same destination
+    pub fn is_one_to_one(&self) -> bool {
+        let mut pieces = split_ranges(vec![(0, usize::MAX)], &self.lines);
+        pieces.sort_by_key(|&(destination, _, _)| destination);
+        pieces
+            .windows(2)
+            .all(|pair| pair[0].0 + pair[0].2 <= pair[1].0)
     }
-    let mut light_to_temperature_map_lines: Vec<(usize, usize, usize)> = Vec::new();
-
-    for i in light_to_temperature_range.0..light_to_temperature_range.1 {
-        let parse = lines[i].split(" ").collect::<Vec<&str>>();
-        light_to_temperature_map_lines.push((
-            parse[0].parse::<usize>().unwrap(),
-            parse[1].parse::<usize>().unwrap(),
-            parse[2].parse::<usize>().unwrap(),
-        ))
+
+    // The map going from destination back to source, None when some number
+    // has more than one source
+    pub fn inverse(&self) -> Option<CategoryMap> {
+        if !self.is_one_to_one() {
+            return None;
+        }
+        Some(CategoryMap {
+            source: self.destination.clone(),
+            destination: self.source.clone(),
+            lines: normalize(
+                split_ranges(vec![(0, usize::MAX)], &self.lines)
+                    .into_iter()
+                    .map(|(destination, source, length)| (source, destination, length))
+                    .collect(),
+            ),
+        })
     }
-    let mut temperature_to_humidity_map_lines: Vec<(usize, usize, usize)> = Vec::new();
-
-    for i in temperature_to_humidity_range.0..temperature_to_humidity_range.1 {
-        let parse = lines[i].split(" ").collect::<Vec<&str>>();
-        temperature_to_humidity_map_lines.push((
-            parse[0].parse::<usize>().unwrap(),
-            parse[1].parse::<usize>().unwrap(),
-            parse[2].parse::<usize>().unwrap(),
-        ))
+
+    // A single map that does the same as applying this map and then `next`
+    pub fn then(&self, next: &CategoryMap) -> CategoryMap {
+        let lines = split_ranges(vec![(0, usize::MAX)], &self.lines)
+            .into_iter()
+            .flat_map(|(middle, source, length)| {
+                split_ranges(vec![(middle, length)], &next.lines)
+                    .into_iter()
+                    .map(move |(destination, from, length)| {
+                        (destination, source + (from - middle), length)
+                    })
+            })
+            .collect();
+        CategoryMap {
+            source: self.source.clone(),
+            destination: next.destination.clone(),
+            lines: normalize(lines),
+        }
     }
-    let mut humidity_to_location_map_lines: Vec<(usize, usize, usize)> = Vec::new();
-
-    for i in humidity_to_location_range.0..humidity_to_location_range.1 {
-        let parse = lines[i].split(" ").collect::<Vec<&str>>();
-        humidity_to_location_map_lines.push((
-            parse[0].parse::<usize>().unwrap(),
-            parse[1].parse::<usize>().unwrap(),
-            parse[2].parse::<usize>().unwrap(),
-        ))
+}
+
+// Drops lines that keep their numbers and any part of a line hidden by an
+// earlier one, then joins lines that continue each other so the result is
+// sorted by source without any overlap
+fn normalize(lines: Vec<MapLine>) -> Vec<MapLine> {
+    let mut normalized: Vec<MapLine> = Vec::new();
+    for (destination, source, length) in split_ranges(vec![(0, usize::MAX)], &lines) {
+        if destination == source {
+            continue;
+        }
+        match normalized.last_mut() {
+            Some(last) if last.1 + last.2 == source && last.0 + last.2 == destination => {
+                last.2 += length
+            }
+            _ => normalized.push((destination, source, length)),
+        }
     }
+    normalized
+}
 
-    vec![
-        seed_to_soil_map_lines,
-        soil_to_fertilizer_map_lines,
-        fertilizer_to_water_map_lines,
-        water_to_light_map_lines,
-        light_to_temperature_map_lines,
-        temperature_to_humidity_map_lines,
-        humidity_to_location_map_lines,
-    ]
+// Printed the same way as a section of the almanac
+impl fmt::Display for CategoryMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}-to-{} map:", self.source, self.destination)?;
+        for (destination, source, length) in &self.lines {
+            writeln!(f, "{} {} {}", destination, source, length)?;
+        }
+        Ok(())
+    }
 }
 
-// Converts a seed number to its corresponding location number.
-fn convert_to_location(seed: usize, map_lines: &Vec<Vec<(usize, usize, usize)>>) -> usize {
-    let soil = map_category(seed, &map_lines[0]);
-    let fertilizer = map_category(soil, &map_lines[1]);
-    let water = map_category(fertilizer, &map_lines[2]);
-    let light = map_category(water, &map_lines[3]);
-    let temperature = map_category(light, &map_lines[4]);
-    let humidity = map_category(temperature, &map_lines[5]);
-    map_category(humidity, &map_lines[6])
+impl Almanac {
+    // Shortest chain of maps converting `from` into `to`, empty when they are
+    // the same category
+    pub fn chain(&self, from: &str, to: &str) -> Result<Vec<&CategoryMap>, AlmanacError> {
+        // Map that first reached each category while searching
+        let mut reached_by: HashMap<&str, Option<&CategoryMap>> = HashMap::from([(from, None)]);
+        let mut queue = VecDeque::from([from]);
+
+        while let Some(category) = queue.pop_front() {
+            if category == to {
+                let mut chain = Vec::new();
+                let mut current = to;
+                while let Some(&Some(map)) = reached_by.get(current) {
+                    chain.push(map);
+                    current = &map.source;
+                }
+                chain.reverse();
+                return Ok(chain);
+            }
+            for map in self.maps.iter().filter(|map| map.source == category) {
+                if !reached_by.contains_key(map.destination.as_str()) {
+                    reached_by.insert(&map.destination, Some(map));
+                    queue.push_back(&map.destination);
+                }
+            }
+        }
+
+        Err(AlmanacError::NoConversion {
+            from: String::from(from),
+            to: String::from(to),
+        })
+    }
+
+    // The whole chain from `from` to `to` collapsed into a single map
+    pub fn compose(&self, from: &str, to: &str) -> Result<CategoryMap, AlmanacError> {
+        Ok(self
+            .chain(from, to)?
+            .iter()
+            .fold(CategoryMap::identity(from), |composed, map| {
+                composed.then(map)
+            }))
+    }
+
+    pub fn convert(&self, value: usize, from: &str, to: &str) -> Result<usize, AlmanacError> {
+        Ok(self
+            .chain(from, to)?
+            .iter()
+            .fold(value, |value, map| map_category(value, &map.lines)))
+    }
+
+    // Same as `convert` for (start, length) ranges
+    pub fn convert_ranges(
+        &self,
+        ranges: Vec<(usize, usize)>,
+        from: &str,
+        to: &str,
+    ) -> Result<Vec<(usize, usize)>, AlmanacError> {
+        Ok(self
+            .chain(from, to)?
+            .iter()
+            .fold(ranges, |ranges, map| map_ranges(ranges, &map.lines)))
+    }
 }
 
-fn map_category(num: usize, mappings: &Vec<(usize, usize, usize)>) -> usize {
+fn map_category(num: usize, mappings: &[MapLine]) -> usize {
     for &(dest_start, src_start, length) in mappings {
         if num >= src_start && num < src_start + length {
             return dest_start + (num - src_start);
@@ -165,74 +296,118 @@ fn map_category(num: usize, mappings: &Vec<(usize, usize, usize)>) -> usize {
     num
 }
 
-pub fn part_one(input: &str) -> usize {
+pub fn part_one(input: &str) -> Result<usize, AlmanacError> {
     // Remove the white lines separating the sections
-    let lines: Vec<&str> = input
-        .split("\n")
-        .collect::<Vec<&str>>()
-        .iter()
-        .filter(|&s| !s.is_empty())
-        .map(|&s| s)
+    let lines: Vec<Line> = parse::lines(input)
+        .filter(|line| !line.text.is_empty())
         .collect();
+    let seed_line = lines.first().ok_or(ParseError::EmptyInput)?;
 
-    let seeds: Vec<usize> = get_seeds(lines[0]);
-    let map_lines = process_map_ranges(lines);
+    let seeds: Vec<usize> = get_seeds(seed_line)?;
+    let almanac = process_almanac(&lines)?;
 
     let locations = seeds
         .iter()
-        .map(|&seed| convert_to_location(seed, &map_lines))
-        .collect::<Vec<usize>>();
-    *locations.iter().min().unwrap()
+        .map(|&seed| almanac.convert(seed, "seed", "location"))
+        .collect::<Result<Vec<usize>, AlmanacError>>()?;
+    // get_seeds makes sure there is at least one seed
+    Ok(*locations.iter().min().unwrap())
 }
 
-pub fn part_two(input: &str) -> i64 {
-    // Remove the white lines separating the sections
-    let lines: Vec<&str> = input
-        .split("\n")
-        .collect::<Vec<&str>>()
-        .iter()
-        .filter(|&s| !s.is_empty())
-        .map(|&s| s)
-        .collect();
+// Splits (start, length) ranges at the boundaries of every mapping line so
+// each piece is either moved by one line or passed through unchanged. Pieces
+// are returned as (destination, source, length) sorted by source.
+pub fn split_ranges(ranges: Vec<(usize, usize)>, mappings: &[MapLine]) -> Vec<MapLine> {
+    let mut pieces = Vec::new();
+    let mut unmapped = ranges;
 
-    let seed_ranges: Vec<(i64, i64)> = get_seeds_part_two(lines[0]);
-    let map_lines = process_map_ranges(lines);
-    let lowest_location = std::sync::atomic::AtomicI64::new(i64::MAX);
-
-    seed_ranges.par_iter().for_each(|seed_range| {
-        let start = seed_range.0;
-        let length = seed_range.1;
+    for &(dest_start, src_start, length) in mappings {
+        let src_end = src_start + length;
+        let mut remaining = Vec::new();
+
+        for (start, range_length) in unmapped {
+            let end = start + range_length;
+            let overlap_start = start.max(src_start);
+            let overlap_end = end.min(src_end);
+            if overlap_start >= overlap_end {
+                remaining.push((start, range_length));
+                continue;
+            }
 
-        // Iterate through all the seed values
-        for i in start..=start + length {
-            let next = transform_seed_value(i, &map_lines);
-            let current_lowest = lowest_location.load(std::sync::atomic::Ordering::Relaxed);
-            if next < current_lowest {
-                lowest_location.store(next, std::sync::atomic::Ordering::Relaxed);
+            pieces.push((
+                dest_start + (overlap_start - src_start),
+                overlap_start,
+                overlap_end - overlap_start,
+            ));
+            // Parts hanging over either side may still match another line
+            if start < overlap_start {
+                remaining.push((start, overlap_start - start));
+            }
+            if overlap_end < end {
+                remaining.push((overlap_end, end - overlap_end));
             }
         }
-    });
+        unmapped = remaining;
+    }
 
-    lowest_location.load(std::sync::atomic::Ordering::Relaxed)
+    // Anything that no line matched keeps its number
+    pieces.extend(
+        unmapped
+            .into_iter()
+            .map(|(start, length)| (start, start, length)),
+    );
+    pieces.sort_by_key(|&(_, source, _)| source);
+    pieces
 }
 
-fn transform_seed_value(mut value: i64, map_lines: &Vec<Vec<(usize, usize, usize)>>) -> i64 {
-    for mapping in map_lines {
-        for &map in mapping {
-            if let Some(new_value) = get_potential_new_value((map.0 as i64, map.1 as i64, map.2 as i64), value) {
-                value = new_value;
-                break;
-            }
-        }
-    }
-    value
+// Pushes (start, length) ranges through a single map
+pub fn map_ranges(ranges: Vec<(usize, usize)>, mappings: &[MapLine]) -> Vec<(usize, usize)> {
+    split_ranges(ranges, mappings)
+        .into_iter()
+        .map(|(destination, _, length)| (destination, length))
+        .collect()
 }
 
-fn get_potential_new_value(map: (i64, i64, i64), from: i64) -> Option<i64> {
-    if from >= map.1 && from < map.1 + map.2 {
-        Some(from + (map.0 - map.1))
-    } else {
-        None
+pub fn part_two(input: &str) -> Result<usize, AlmanacError> {
+    // Remove the white lines separating the sections
+    let lines: Vec<Line> = parse::lines(input)
+        .filter(|line| !line.text.is_empty())
+        .collect();
+    let seed_line = lines.first().ok_or(ParseError::EmptyInput)?;
+
+    let seed_ranges: Vec<(usize, usize)> = get_seeds_part_two(seed_line)?
+        .into_iter()
+        .filter(|&(_, length)| length > 0)
+        .collect();
+    let almanac = process_almanac(&lines)?;
+
+    let locations = almanac.convert_ranges(seed_ranges, "seed", "location")?;
+
+    // Every range that is left has at least one number in it
+    Ok(locations
+        .iter()
+        .map(|&(start, _)| start)
+        .min()
+        .ok_or_else(|| seed_line.missing("a seed range length"))?)
+}
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    fn day(&self) -> u8 {
+        5
+    }
+
+    fn title(&self) -> &'static str {
+        "If You Give A Seed A Fertilizer"
+    }
+
+    fn part_one(&self, input: &str) -> Result<Answer, Box<dyn Error>> {
+        part_one(input).map(Answer::from).map_err(Into::into)
+    }
+
+    fn part_two(&self, input: &str) -> Option<Result<Answer, Box<dyn Error>>> {
+        Some(part_two(input).map(Answer::from).map_err(Into::into))
     }
 }
 
@@ -241,8 +416,9 @@ mod tests {
     use super::*;
     #[test]
     fn test_get_seeds() {
-        let seed_line = "seeds: 79 14 55 13";
-        assert_eq!(get_seeds(seed_line), vec![79, 14, 55, 13]);
+        let seed_line = Line::new(0, "seeds: 79 14 55 13");
+        assert_eq!(get_seeds(&seed_line), Ok(vec![79, 14, 55, 13]));
+        assert_eq!(get_seeds_part_two(&seed_line), Ok(vec![(79, 14), (55, 13)]));
     }
 
     #[test]
@@ -280,7 +456,7 @@ temperature-to-humidity map:
 humidity-to-location map:
 60 56 37
 56 93 4";
-        assert_eq!(part_one(input), 35);
+        assert_eq!(part_one(input), Ok(35));
     }
 
     #[test]
@@ -318,6 +494,145 @@ temperature-to-humidity map:
 humidity-to-location map:
 60 56 37
 56 93 4";
-        assert_eq!(part_two(input), 46);
+        assert_eq!(part_two(input), Ok(46));
+    }
+
+    #[test]
+    fn test_map_ranges() {
+        // Split on both sides of the 50..98 mapping and passed through after it
+        assert_eq!(
+            map_ranges(vec![(40, 20), (95, 10)], &[(50, 98, 2), (52, 50, 48)]),
+            vec![(40, 10), (52, 10), (97, 3), (50, 2), (100, 5)]
+        );
+        assert_eq!(map_ranges(vec![(0, 5)], &[]), vec![(0, 5)]);
+    }
+
+    #[test]
+    fn test_almanac_chain() {
+        // Sections out of order with an extra category that is never used
+        let input = "seeds: 79 14 55 13
+
+soil-to-water map:
+10 0 100
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+seed-to-color map:
+0 0 5
+
+water-to-location map:
+0 60 10";
+        let lines: Vec<Line> = parse::lines(input)
+            .filter(|line| !line.text.is_empty())
+            .collect();
+        let almanac = process_almanac(&lines).unwrap();
+        assert_eq!(almanac.maps.len(), 4);
+        assert_eq!(almanac.convert(79, "seed", "soil"), Ok(81));
+        assert_eq!(almanac.convert(79, "seed", "water"), Ok(91));
+        assert_eq!(almanac.convert(55, "seed", "location"), Ok(7));
+        assert_eq!(almanac.convert(3, "water", "water"), Ok(3));
+        assert_eq!(
+            almanac.convert(3, "location", "seed"),
+            Err(AlmanacError::NoConversion {
+                from: String::from("location"),
+                to: String::from("seed"),
+            })
+        );
+        assert_eq!(part_one(input), Ok(7));
+    }
+
+    #[test]
+    fn test_compose_and_inverse() {
+        let input = "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4";
+        let lines: Vec<Line> = parse::lines(input)
+            .filter(|line| !line.text.is_empty())
+            .collect();
+        let almanac = process_almanac(&lines).unwrap();
+        let composed = almanac.compose("seed", "location").unwrap();
+        for seed in 0..120 {
+            assert_eq!(
+                Ok(composed.apply(seed)),
+                almanac.convert(seed, "seed", "location")
+            );
+        }
+        assert_eq!(composed.preimages(46), vec![82]);
+
+        let inverse = composed.inverse().unwrap();
+        assert_eq!(
+            inverse.to_string().lines().next(),
+            Some("location-to-seed map:")
+        );
+        for seed in 0..120 {
+            assert_eq!(inverse.apply(composed.apply(seed)), seed);
+        }
+
+        let soil = &almanac.maps[0];
+        assert_eq!(soil.to_string(), "seed-to-soil map:\n50 98 2\n52 50 48\n");
+        assert_eq!(soil.then(&soil.inverse().unwrap()).lines, vec![]);
+
+        // 0..5 and 10..15 both end up at 0..5
+        let merging = CategoryMap {
+            source: String::from("a"),
+            destination: String::from("b"),
+            lines: vec![(0, 10, 5)],
+        };
+        assert_eq!(merging.preimages(3), vec![3, 13]);
+        assert_eq!(merging.preimages(12), vec![]);
+        assert_eq!(merging.inverse(), None);
+    }
+
+    #[test]
+    fn test_map_line_error() {
+        let input = "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50
+
+soil-to-fertilizer map:
+0 15 37";
+        assert_eq!(
+            part_one(input),
+            Err(AlmanacError::Parse(ParseError::UnexpectedToken {
+                line: 5,
+                column: 1,
+                token: String::from("52 50"),
+                expected: "a destination, source and length",
+            }))
+        );
     }
 }
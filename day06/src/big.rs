@@ -0,0 +1,70 @@
+use crate::{parse_joined, winning_hold_times, Number};
+use common::parse::ParseError;
+use num_bigint::BigUint;
+use std::ops::RangeInclusive;
+
+// Same as Race for numbers of any size
+#[derive(Debug)]
+pub struct BigRace {
+    time: BigUint,
+    distance: BigUint,
+}
+
+impl Number for BigUint {
+    fn sqrt(&self) -> Self {
+        BigUint::sqrt(self)
+    }
+}
+
+impl BigRace {
+    pub fn new(time: BigUint, distance: BigUint) -> Self {
+        BigRace { time, distance }
+    }
+
+    // Hold times that beat the record distance, None when none of them do
+    pub fn winning_hold_times(&self) -> Option<RangeInclusive<BigUint>> {
+        winning_hold_times(self.time.clone(), self.distance.clone())
+    }
+
+    pub fn count_winning_hold_times(&self) -> BigUint {
+        self.winning_hold_times()
+            .map_or(BigUint::ZERO, |holds| holds.end() - holds.start() + 1_u32)
+    }
+}
+
+pub fn process_input_part_two(lines: Vec<&str>) -> Result<BigRace, ParseError> {
+    let (time, distance) = parse_joined(&lines)?;
+    Ok(BigRace::new(time, distance))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Race;
+
+    #[test]
+    fn test_matches_race() {
+        for time in 0..40_u64 {
+            for distance in 0..400_u64 {
+                let race = Race { time, distance };
+                let big_race = BigRace::new(BigUint::from(time), BigUint::from(distance));
+                assert_eq!(
+                    big_race.count_winning_hold_times(),
+                    BigUint::from(race.count_winning_hold_times())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_longer_than_u64() {
+        let race = process_input_part_two(vec![
+            "Time:      7  15   30  4000000000  000000000",
+            "Distance:  9  40  200",
+        ])
+        .unwrap();
+        // With a time of t and a tiny record nearly every hold wins
+        let time: BigUint = "715304000000000000000000".parse().unwrap();
+        assert_eq!(race.count_winning_hold_times(), time - 1_u32);
+    }
+}
@@ -1,3 +1,24 @@
+use common::{
+    parse::{Line, ParseError},
+    Answer, Solution,
+};
+use std::{
+    error::Error,
+    ops::{Add, Div, Mul, RangeInclusive, Sub},
+    str::FromStr,
+};
+
+#[cfg(feature = "bigint")]
+mod big;
+#[cfg(feature = "bigint")]
+pub use big::BigRace;
+
+// Number of winning hold times for part two, any size with the bigint feature
+#[cfg(feature = "bigint")]
+pub type Count = num_bigint::BigUint;
+#[cfg(not(feature = "bigint"))]
+pub type Count = u64;
+
 #[derive(Debug)]
 pub struct Race {
     time: u64,
@@ -5,67 +26,201 @@ pub struct Race {
 }
 
 impl Race {
-    fn get_possible_times(&self) -> Vec<u64> {
-        let mut results: Vec<u64> = Vec::new();
-        for i in 0..self.time+1 {
-            let possible_distance = i * (self.time - i);
-            if possible_distance > self.distance {
-                results.push(possible_distance);
-            }
-        }
-        results
+    // Hold times that beat the record distance, None when none of them do.
+    // Worked out in a u128 which fits any distance a u64 time can travel.
+    pub fn winning_hold_times(&self) -> Option<RangeInclusive<u64>> {
+        let holds = winning_hold_times(self.time as u128, self.distance as u128)?;
+        Some(*holds.start() as u64..=*holds.end() as u64)
+    }
+
+    pub fn count_winning_hold_times(&self) -> u64 {
+        self.winning_hold_times()
+            .map_or(0, |holds| holds.end() - holds.start() + 1)
+    }
+}
+
+// What `winning_hold_times` needs of a number, the arithmetic operators and
+// a square root rounded down
+pub(crate) trait Number:
+    Clone
+    + Ord
+    + From<u8>
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+{
+    fn sqrt(&self) -> Self;
+}
+
+impl Number for u128 {
+    fn sqrt(&self) -> Self {
+        integer_sqrt(*self)
+    }
+}
+
+// Winning holds are the integers strictly between the roots of
+// hold * (time - hold) = distance, which are (time ± √(time² - 4 * distance)) / 2
+pub(crate) fn winning_hold_times<N: Number>(time: N, distance: N) -> Option<RangeInclusive<N>> {
+    let wins = |hold: &N| hold.clone() * (time.clone() - hold.clone()) > distance;
+    let square = time.clone() * time.clone();
+    let record = distance.clone() * N::from(4);
+    if square < record {
+        return None;
+    }
+    let root = (square - record).sqrt();
+
+    // The integer root can be off by one from the real root so step to
+    // the first hold that wins, a hold that ties the record does not
+    let (zero, one, two) = (N::from(0), N::from(1), N::from(2));
+    let half = time.clone() / two.clone();
+    let mut low = (time.clone() - root.min(time.clone())) / two;
+    while low > zero && wins(&(low.clone() - one.clone())) {
+        low = low - one.clone();
     }
+    while low <= half && !wins(&low) {
+        low = low + one.clone();
+    }
+    if low > half {
+        return None;
+    }
+    // Holding for h and for time - h travel the same distance
+    let high = time - low.clone();
+    Some(low..=high)
+}
+
+// Largest integer whose square is at most `value`. The float estimate is
+// corrected with checked arithmetic since squaring values near the top of
+// the range overflows even a u128.
+fn integer_sqrt(value: u128) -> u128 {
+    let mut root = (value as f64).sqrt() as u128;
+    while root.checked_mul(root).is_none_or(|square| square > value) {
+        root -= 1;
+    }
+    while (root + 1)
+        .checked_mul(root + 1)
+        .is_some_and(|square| square <= value)
+    {
+        root += 1;
+    }
+    root
+}
+
+// Returns the numbers following the label of a "Time:" or "Distance:" line
+pub(crate) fn get_values<'a>(
+    lines: &[&'a str],
+    index: usize,
+    expected: &'static str,
+) -> Result<(Line<'a>, &'a str), ParseError> {
+    let text = match lines.get(index) {
+        Some(&text) => text,
+        None if index == 0 => return Err(ParseError::EmptyInput),
+        None => {
+            return Err(ParseError::MissingToken {
+                line: index + 1,
+                column: 1,
+                expected,
+            })
+        }
+    };
+    let line = Line::new(index, text);
+    let (_, values) = line.split_once(text, ":", expected)?;
+    Ok((line, values))
 }
 
-pub fn process_input(lines: Vec<&str>) -> Vec<Race> {
-    let times: Vec<u64> = lines[0].split(":").collect::<Vec<&str>>()[1]
-        .split_ascii_whitespace()
-        .map(|time| time.parse::<u64>().unwrap())
-        .collect();
-    let distances: Vec<u64> = lines[1].split(":").collect::<Vec<&str>>()[1]
-        .split_ascii_whitespace()
-        .map(|time| time.parse::<u64>().unwrap())
-        .collect();
-
-    times
+pub fn process_input(lines: Vec<&str>) -> Result<Vec<Race>, ParseError> {
+    let (time_line, times) = get_values(&lines, 0, "times")?;
+    let (distance_line, distances) = get_values(&lines, 1, "distances")?;
+    let times: Vec<u64> = time_line.parse_all(times)?;
+    let distances: Vec<u64> = distance_line.parse_all(distances)?;
+    if distances.len() < times.len() {
+        return Err(distance_line.missing("a distance for every time"));
+    }
+
+    Ok(times
         .iter()
         .zip(distances)
         .map(|(&time, distance)| Race { time, distance })
-        .collect()
+        .collect())
 }
 
-pub fn process_input_part_two(lines: Vec<&str>) -> Race {
-    let time: u64 = lines[0].split(":").collect::<Vec<&str>>()[1]
-        .split_ascii_whitespace()
-        .collect::<Vec<&str>>().join("").parse::<u64>().unwrap();
+// Time and distance of the single race in part two, read by ignoring the
+// spaces between the digits
+pub(crate) fn parse_joined<T: FromStr>(lines: &[&str]) -> Result<(T, T), ParseError> {
+    let (time_line, times) = get_values(lines, 0, "times")?;
+    let (distance_line, distances) = get_values(lines, 1, "distances")?;
+    // Validate each group of digits first so errors point at the bad token
+    time_line.parse_all::<T>(times)?;
+    distance_line.parse_all::<T>(distances)?;
 
-    let distance: u64 = lines[1].split(":").collect::<Vec<&str>>()[1]
-        .split_ascii_whitespace()
-        .collect::<Vec<&str>>().join("").parse::<u64>().unwrap();
+    let time: T = time_line.parse(
+        &times
+            .split_ascii_whitespace()
+            .collect::<Vec<&str>>()
+            .join(""),
+    )?;
 
-    Race{
-        time,
-        distance
-    }
+    let distance: T = distance_line.parse(
+        &distances
+            .split_ascii_whitespace()
+            .collect::<Vec<&str>>()
+            .join(""),
+    )?;
+
+    Ok((time, distance))
 }
 
-pub fn part_one(input: &str) -> usize {
+pub fn process_input_part_two(lines: Vec<&str>) -> Result<Race, ParseError> {
+    let (time, distance) = parse_joined(&lines)?;
+    Ok(Race { time, distance })
+}
+
+pub fn part_one(input: &str) -> Result<u64, ParseError> {
     let mut lines = input.split("\n").collect::<Vec<&str>>();
-    if lines[lines.len() - 1] == "" {
+    if lines[lines.len() - 1].is_empty() {
         lines.pop();
     }
-    let races = process_input(lines);
-    races.iter().map(|r| {
-        r.get_possible_times().iter().count()
-    }).product()
+    let races = process_input(lines)?;
+    Ok(races.iter().map(|r| r.count_winning_hold_times()).product())
 }
 
-pub fn part_two(input: &str) -> usize {
+pub fn part_two(input: &str) -> Result<Count, ParseError> {
     let mut lines = input.split("\n").collect::<Vec<&str>>();
-    if lines[lines.len() - 1] == "" {
+    if lines[lines.len() - 1].is_empty() {
         lines.pop();
     }
-    process_input_part_two(lines).get_possible_times().iter().count()
+    #[cfg(feature = "bigint")]
+    let race = big::process_input_part_two(lines)?;
+    #[cfg(not(feature = "bigint"))]
+    let race = process_input_part_two(lines)?;
+    Ok(race.count_winning_hold_times())
+}
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    fn day(&self) -> u8 {
+        6
+    }
+
+    fn title(&self) -> &'static str {
+        "Wait For It"
+    }
+
+    fn part_one(&self, input: &str) -> Result<Answer, Box<dyn Error>> {
+        part_one(input).map(Answer::from).map_err(Into::into)
+    }
+
+    fn part_two(&self, input: &str) -> Option<Result<Answer, Box<dyn Error>>> {
+        #[cfg(feature = "bigint")]
+        let answer = part_two(input).map(|count| match u128::try_from(&count) {
+            Ok(count) => Answer::from(count),
+            Err(_) => Answer::from(count.to_string()),
+        });
+        #[cfg(not(feature = "bigint"))]
+        let answer = part_two(input).map(Answer::from);
+        Some(answer.map_err(Into::into))
+    }
 }
 
 #[cfg(test)]
@@ -75,13 +230,72 @@ mod tests {
     fn test_part_one() {
         let input = "Time:      7  15   30
 Distance:  9  40  200";
-        assert_eq!(part_one(input), 288);
+        assert_eq!(part_one(input), Ok(288));
     }
     #[test]
     fn test_part_two() {
         let input = "Time:      7  15   30
 Distance:  9  40  200";
-        assert_eq!(part_two(input), 71503);
+        assert_eq!(part_two(input), Ok(Count::from(71503_u32)));
     }
+    #[test]
+    fn test_winning_hold_times() {
+        // Holding for 10 or 20 ties the record of 200 which is not a win
+        let race = Race {
+            time: 30,
+            distance: 200,
+        };
+        assert_eq!(race.winning_hold_times(), Some(11..=19));
 
+        for time in 0..60 {
+            for distance in 0..1000 {
+                let race = Race { time, distance };
+                let expected = (0..=time)
+                    .filter(|&hold| hold * (time - hold) > distance)
+                    .count() as u64;
+                assert_eq!(race.count_winning_hold_times(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_winning_hold_times_near_max() {
+        let race = Race {
+            time: u64::MAX,
+            distance: u64::MAX,
+        };
+        assert_eq!(race.winning_hold_times(), Some(2..=u64::MAX - 2));
+        let race = Race {
+            time: u64::MAX,
+            distance: 0,
+        };
+        assert_eq!(race.count_winning_hold_times(), u64::MAX - 1);
+        // The best hold of 2^31 only ties the record
+        let race = Race {
+            time: 1 << 32,
+            distance: 1 << 62,
+        };
+        assert_eq!(race.winning_hold_times(), None);
+        assert_eq!(integer_sqrt(u128::MAX), u64::MAX as u128);
+    }
+
+    #[test]
+    fn test_process_input_error() {
+        assert_eq!(
+            part_one("Time:      7  15   30\nDistance:  9  4O  200").err(),
+            Some(ParseError::InvalidNumber {
+                line: 2,
+                column: 15,
+                token: String::from("4O"),
+            })
+        );
+        assert_eq!(
+            part_two("Time:      7  15   30").err(),
+            Some(ParseError::MissingToken {
+                line: 2,
+                column: 1,
+                expected: "distances",
+            })
+        );
+    }
 }
@@ -1,2 +1,43 @@
+use common::{Answer, Solution};
+use std::{error::Error, io::BufRead};
+
 pub mod part1;
 pub mod part2;
+pub mod report;
+pub mod rules;
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    fn day(&self) -> u8 {
+        7
+    }
+
+    fn title(&self) -> &'static str {
+        "Camel Cards"
+    }
+
+    fn part_one(&self, input: &str) -> Result<Answer, Box<dyn Error>> {
+        part1::part_one(input).map(Answer::from).map_err(Into::into)
+    }
+
+    fn part_two(&self, input: &str) -> Option<Result<Answer, Box<dyn Error>>> {
+        Some(part2::part_two(input).map(Answer::from).map_err(Into::into))
+    }
+
+    fn stream_part_one(&self, reader: &mut dyn BufRead) -> Option<Result<Answer, Box<dyn Error>>> {
+        Some(
+            part1::stream_part_one(reader)
+                .map(Answer::from)
+                .map_err(Into::into),
+        )
+    }
+
+    fn stream_part_two(&self, reader: &mut dyn BufRead) -> Option<Result<Answer, Box<dyn Error>>> {
+        Some(
+            part2::stream_part_two(reader)
+                .map(Answer::from)
+                .map_err(Into::into),
+        )
+    }
+}
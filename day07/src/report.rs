@@ -0,0 +1,185 @@
+use crate::rules::{input_lines, Rules, Score};
+use common::parse::ParseError;
+use serde::Serialize;
+use std::fmt;
+
+// Why a hand beat the hand ranked right below it when both have the same type
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TieBreak {
+    pub against: String,
+    // 1-based position of the first card that differs
+    pub position: usize,
+    pub card: char,
+    pub other: char,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RankedHand {
+    pub hand: String,
+    #[serde(rename = "type")]
+    pub score: Score,
+    // The hand with wild cards replaced by the cards they stand in for
+    pub best_hand: String,
+    pub rank: usize,
+    pub bet: usize,
+    pub winnings: usize,
+    pub tie_break: Option<TieBreak>,
+}
+
+// Every hand from the weakest to the strongest showing how the total is made
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Report {
+    pub hands: Vec<RankedHand>,
+    pub total: usize,
+}
+
+impl Report {
+    pub fn new(rules: &Rules, input: &str) -> Result<Report, ParseError> {
+        let sorted_hands = rules.sorted_hands(input_lines(input))?;
+        let mut hands: Vec<RankedHand> = Vec::new();
+
+        for (index, &(hand, ((score, _), bet))) in sorted_hands.iter().enumerate() {
+            let tie_break = index
+                .checked_sub(1)
+                .map(|below| &sorted_hands[below])
+                .filter(|(_, ((below_score, _), _))| *below_score == score)
+                .and_then(|&(below, _)| {
+                    let (position, (card, other)) = hand
+                        .chars()
+                        .zip(below.chars())
+                        .enumerate()
+                        .find(|(_, (card, other))| card != other)?;
+                    Some(TieBreak {
+                        against: String::from(below),
+                        position: position + 1,
+                        card,
+                        other,
+                    })
+                });
+            hands.push(RankedHand {
+                hand: String::from(hand),
+                score,
+                best_hand: rules.best_substitution(hand),
+                rank: index + 1,
+                bet,
+                winnings: bet * (index + 1),
+                tie_break,
+            });
+        }
+
+        Ok(Report {
+            total: hands.iter().map(|hand| hand.winnings).sum(),
+            hands,
+        })
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("report only holds plain data")
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .hands
+            .iter()
+            .map(|hand| hand.hand.len())
+            .max()
+            .unwrap_or(0)
+            .max("hand".len());
+        writeln!(
+            f,
+            "{:>5}  {:<width$}  {:<15}  {:<width$}  {:>6}  {:>9}  tie-break",
+            "rank",
+            "hand",
+            "type",
+            "best",
+            "bet",
+            "winnings",
+            width = width
+        )?;
+        for hand in &self.hands {
+            write!(
+                f,
+                "{:>5}  {:<width$}  {:<15}  {:<width$}  {:>6}  {:>9}",
+                hand.rank,
+                hand.hand,
+                hand.score,
+                hand.best_hand,
+                hand.bet,
+                hand.winnings,
+                width = width
+            )?;
+            if let Some(tie_break) = &hand.tie_break {
+                write!(
+                    f,
+                    "  beats {} on card {} ({} > {})",
+                    tie_break.against, tie_break.position, tie_break.card, tie_break.other
+                )?;
+            }
+            writeln!(f)?;
+        }
+        writeln!(f, "total: {}", self.total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let input = "32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483";
+        let report = Report::new(&Rules::standard(), input).unwrap();
+        assert_eq!(report.total, 6440);
+        assert_eq!(
+            report.to_string(),
+            " rank  hand   type             best      bet   winnings  tie-break
+    1  32T3K  one pair         32T3K     765        765
+    2  KTJJT  two pair         KTJJT     220        440
+    3  KK677  two pair         KK677      28         84  beats KTJJT on card 2 (K > T)
+    4  T55J5  three of a kind  T55J5     684       2736
+    5  QQQJA  three of a kind  QQQJA     483       2415  beats T55J5 on card 1 (Q > T)
+total: 6440
+"
+        );
+
+        let report = Report::new(&Rules::jokers(), input).unwrap();
+        assert_eq!(report.total, 5905);
+        let top = &report.hands[4];
+        assert_eq!(top.hand, "KTJJT");
+        assert_eq!(top.score, Score::FourOfAKind);
+        assert_eq!(top.best_hand, "KTTTT");
+        assert_eq!(
+            top.tie_break,
+            Some(TieBreak {
+                against: String::from("QQQJA"),
+                position: 1,
+                card: 'K',
+                other: 'Q',
+            })
+        );
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json["total"], 5905);
+        assert_eq!(json["hands"][4]["type"], "four_of_a_kind");
+        assert_eq!(json["hands"][4]["tie_break"]["against"], "QQQJA");
+        assert_eq!(json["hands"][0]["tie_break"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn test_report_short_hands() {
+        let report = Report::new(&Rules::new("23AK", None, 2), "AK 2\n22 3").unwrap();
+        assert_eq!(
+            report.to_string(),
+            " rank  hand  type             best     bet   winnings  tie-break
+    1  AK    high card        AK         2          2
+    2  22    one pair         22         3          6
+total: 8
+"
+        );
+    }
+}
@@ -0,0 +1,373 @@
+use common::{
+    parse::{Line, ParseError},
+    read::{fold_lines, ReadError},
+};
+use serde::Serialize;
+use std::{fmt, io::BufRead};
+
+// Hand types from weakest to strongest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Score {
+    HighCard = 0,
+    OnePair = 1,
+    TwoPair = 2,
+    ThreeOfAKind = 3,
+    FullHouse = 4,
+    FourOfAKind = 5,
+    FiveOfAKind = 6,
+}
+
+impl Score {
+    // Type of a hand given how many times each card shows up, sorted from
+    // the most common card down. Hands bigger than 5 cards use the best
+    // type their largest groups make.
+    pub fn from_counts(counts: &[usize]) -> Score {
+        let most = counts.first().copied().unwrap_or(0);
+        let second = counts.get(1).copied().unwrap_or(0);
+        match (most, second) {
+            (5.., _) => Score::FiveOfAKind,
+            (4, _) => Score::FourOfAKind,
+            (3, 2..) => Score::FullHouse,
+            (3, _) => Score::ThreeOfAKind,
+            (2, 2) => Score::TwoPair,
+            (2, _) => Score::OnePair,
+            _ => Score::HighCard,
+        }
+    }
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Score::HighCard => "high card",
+            Score::OnePair => "one pair",
+            Score::TwoPair => "two pair",
+            Score::ThreeOfAKind => "three of a kind",
+            Score::FullHouse => "full house",
+            Score::FourOfAKind => "four of a kind",
+            Score::FiveOfAKind => "five of a kind",
+        };
+        // Pad so the type lines up in tables
+        f.pad(name)
+    }
+}
+
+// Hand type and the strength of every card in order, comparing these ranks
+// two hands
+pub type RankKey = (Score, Vec<usize>);
+
+// A hand's rank key along with its bet
+pub type ScoredHand = (RankKey, usize);
+
+// How hands are played, which cards exist and how they are ordered, which
+// card if any is wild and how many cards make up a hand
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    // Every card from weakest to strongest
+    cards: Vec<char>,
+    wild: Option<char>,
+    hand_size: usize,
+}
+
+impl Rules {
+    // Panics when the wild card is not one of the cards
+    pub fn new(cards: &str, wild: Option<char>, hand_size: usize) -> Self {
+        let cards: Vec<char> = cards.chars().collect();
+        if let Some(wild) = wild {
+            assert!(cards.contains(&wild), "wild card {:?} is not a card", wild);
+        }
+        Rules {
+            cards,
+            wild,
+            hand_size,
+        }
+    }
+
+    pub fn standard() -> Self {
+        Rules::new("23456789TJQKA", None, 5)
+    }
+
+    // J is a joker that can stand in for any card but is the weakest card
+    // when breaking ties
+    pub fn jokers() -> Self {
+        Rules::new("J23456789TQKA", Some('J'), 5)
+    }
+
+    pub fn hand_size(&self) -> usize {
+        self.hand_size
+    }
+
+    pub fn wild(&self) -> Option<char> {
+        self.wild
+    }
+
+    pub fn strength(&self, card: char) -> Option<usize> {
+        self.cards.iter().position(|&c| c == card)
+    }
+
+    // Wild cards join whichever card there is the most of since that always
+    // makes the strongest type
+    pub fn score(&self, hand: &str) -> Score {
+        let mut counts = vec![0; self.cards.len()];
+        let mut wild_count = 0;
+        for card in hand.chars() {
+            if Some(card) == self.wild {
+                wild_count += 1;
+            } else if let Some(strength) = self.strength(card) {
+                counts[strength] += 1;
+            }
+        }
+        counts.retain(|&count| count > 0);
+        counts.sort_by(|a, b| b.cmp(a));
+        match counts.first_mut() {
+            Some(most) => *most += wild_count,
+            None => counts.push(wild_count),
+        }
+        Score::from_counts(&counts)
+    }
+
+    // The hand with every wild card replaced by the card it joins in
+    // `score`, the strongest card when the whole hand is wild
+    pub fn best_substitution(&self, hand: &str) -> String {
+        let Some(wild) = self.wild else {
+            return String::from(hand);
+        };
+        let mut counts = vec![0; self.cards.len()];
+        for card in hand.chars().filter(|&card| card != wild) {
+            if let Some(strength) = self.strength(card) {
+                counts[strength] += 1;
+            }
+        }
+        // max_by_key keeps the last maximum so ties go to the stronger card
+        let substitute = counts
+            .iter()
+            .enumerate()
+            .filter(|&(strength, _)| self.cards[strength] != wild)
+            .max_by_key(|&(_, &count)| count)
+            .map_or(wild, |(strength, _)| self.cards[strength]);
+        hand.chars()
+            .map(|card| if card == wild { substitute } else { card })
+            .collect()
+    }
+
+    // Hands are compared by type first and then card by card
+    pub fn rank_key(&self, hand: &str) -> RankKey {
+        (
+            self.score(hand),
+            hand.chars()
+                .filter_map(|card| self.strength(card))
+                .collect(),
+        )
+    }
+
+    // Makes sure a hand is made of known cards and is the right size
+    pub fn validate_hand(&self, line: &Line, hand: &str) -> Result<(), ParseError> {
+        if let Some((index, card)) = hand
+            .chars()
+            .enumerate()
+            .find(|&(_, card)| self.strength(card).is_none())
+        {
+            return Err(line.unexpected_char(line.column(hand) - 1 + index, card, "a card"));
+        }
+        if hand.chars().count() != self.hand_size {
+            return Err(line.unexpected(hand, "a hand with the right number of cards"));
+        }
+        Ok(())
+    }
+
+    // Bits needed for the strength of one card
+    fn card_bits(&self) -> u32 {
+        (usize::BITS - self.cards.len().saturating_sub(1).leading_zeros()).max(1)
+    }
+
+    // Whether the type and every card of a hand fit in a u64
+    pub fn packs_rank_keys(&self) -> bool {
+        3 + self.card_bits() as usize * self.hand_size <= u64::BITS as usize
+    }
+
+    // The rank key packed into one integer, the type in the top bits
+    // followed by the strength of every card. None when the hands are too
+    // big for that to fit in a u64.
+    pub fn packed_rank_key(&self, hand: &str) -> Option<u64> {
+        self.packs_rank_keys().then(|| self.pack_rank_key(hand))
+    }
+
+    // Only meaningful when `packs_rank_keys` holds
+    fn pack_rank_key(&self, hand: &str) -> u64 {
+        let bits = self.card_bits();
+        hand.chars()
+            .filter_map(|card| self.strength(card))
+            .fold(self.score(hand) as u64, |key, strength| {
+                key << bits | strength as u64
+            })
+    }
+
+    // A hand and its bet, making sure the hand is valid
+    fn parse_bet<'a>(&self, line: &Line<'a>) -> Result<(&'a str, usize), ParseError> {
+        let (hand, bet) = line.split_once(line.text, " ", "a bet")?;
+        let bet = line.parse::<usize>(bet)?;
+        self.validate_hand(line, hand)?;
+        Ok((hand, bet))
+    }
+
+    // A single hand along with its rank key and bet
+    pub fn parse_hand<'a>(&self, line: &Line<'a>) -> Result<(&'a str, ScoredHand), ParseError> {
+        let (hand, bet) = self.parse_bet(line)?;
+        Ok((hand, (self.rank_key(hand), bet)))
+    }
+
+    // Every hand along with its rank key and bet sorted from the weakest to
+    // the strongest
+    pub fn sorted_hands<'a>(
+        &self,
+        lines: Vec<&'a str>,
+    ) -> Result<Vec<(&'a str, ScoredHand)>, ParseError> {
+        let mut sorted_hands: Vec<(&str, ScoredHand)> = lines
+            .iter()
+            .enumerate()
+            .map(|(index, &text)| self.parse_hand(&Line::new(index, text)))
+            .collect::<Result<Vec<(&str, ScoredHand)>, ParseError>>()?;
+
+        sorted_hands.sort_by(|a, b| a.1 .0.cmp(&b.1 .0));
+        Ok(sorted_hands)
+    }
+
+    pub fn process_input(&self, lines: Vec<&str>) -> Result<Vec<ScoredHand>, ParseError> {
+        Ok(self
+            .sorted_hands(lines)?
+            .into_iter()
+            .map(|(_, scored_hand)| scored_hand)
+            .collect())
+    }
+
+    // Sum of every bet multiplied by the rank of its hand
+    pub fn total_winnings(&self, input: &str) -> Result<usize, ParseError> {
+        Ok(winnings(&self.process_input(input_lines(input))?))
+    }
+
+    // Same as `total_winnings` reading a hand at a time. Every hand still
+    // has to be kept to rank them, packed into an integer next to its bet
+    // whenever the rules allow it.
+    pub fn stream_total_winnings<R: BufRead>(&self, reader: R) -> Result<usize, ReadError> {
+        if self.packs_rank_keys() {
+            self.stream_winnings(reader, |hand| self.pack_rank_key(hand))
+        } else {
+            self.stream_winnings(reader, |hand| self.rank_key(hand))
+        }
+    }
+
+    fn stream_winnings<R, K, F>(&self, reader: R, rank_key: F) -> Result<usize, ReadError>
+    where
+        R: BufRead,
+        K: Ord,
+        F: Fn(&str) -> K,
+    {
+        let mut hands = fold_lines(reader, Vec::new(), |mut hands, line| {
+            let (hand, bet) = self.parse_bet(&line)?;
+            hands.push((rank_key(hand), bet));
+            Ok(hands)
+        })?;
+        hands.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(winnings(&hands))
+    }
+}
+
+fn winnings<K>(sorted_hands: &[(K, usize)]) -> usize {
+    sorted_hands
+        .iter()
+        .enumerate()
+        .map(|(index, hand)| hand.1 * (index + 1))
+        .sum()
+}
+
+pub(crate) fn input_lines(input: &str) -> Vec<&str> {
+    let mut lines = input.split("\n").collect::<Vec<&str>>();
+    if lines[lines.len() - 1].is_empty() {
+        lines.pop();
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score() {
+        let rules = Rules::standard();
+        assert_eq!(rules.score("32T3K"), Score::OnePair);
+        assert_eq!(rules.score("KTJJT"), Score::TwoPair);
+        assert_eq!(rules.score("T55J5"), Score::ThreeOfAKind);
+        assert_eq!(rules.score("23332"), Score::FullHouse);
+
+        let rules = Rules::jokers();
+        assert_eq!(rules.score("KTJJT"), Score::FourOfAKind);
+        assert_eq!(rules.score("JJJJJ"), Score::FiveOfAKind);
+        assert_eq!(rules.score("2345J"), Score::OnePair);
+        assert_eq!(rules.score("2J3J4"), Score::ThreeOfAKind);
+        assert_eq!(rules.rank_key("J2345").1, vec![0, 1, 2, 3, 4]);
+        assert_eq!(rules.best_substitution("KTJJT"), "KTTTT");
+        assert_eq!(rules.best_substitution("2J3J4"), "24344");
+        assert_eq!(rules.best_substitution("JJJJJ"), "AAAAA");
+        assert_eq!(Rules::standard().best_substitution("KTJJT"), "KTJJT");
+    }
+
+    #[test]
+    fn test_packed_rank_key() {
+        let hands = [
+            "32T3K", "T55J5", "KK677", "KTJJT", "QQQJA", "JJJJJ", "2345J",
+        ];
+        for rules in [Rules::standard(), Rules::jokers()] {
+            for a in hands {
+                for b in hands {
+                    assert_eq!(
+                        rules.packed_rank_key(a).cmp(&rules.packed_rank_key(b)),
+                        rules.rank_key(a).cmp(&rules.rank_key(b)),
+                        "{} against {}",
+                        a,
+                        b
+                    );
+                }
+            }
+        }
+        // One pair, then 3 2 T 3 K a nibble each
+        assert_eq!(Rules::standard().packed_rank_key("32T3K"), Some(0x1_1081b));
+
+        // Too many cards in a hand to pack, streaming falls back to the
+        // unpacked rank key and still matches `total_winnings`
+        let rules = Rules::new("23456789TJQKA", None, 16);
+        assert_eq!(rules.packed_rank_key("2222222222222222"), None);
+        let input = "AAAAAAAAAAAAAAAK 3\n2345678923456789 5\nAAAAAAAAAAAAAAAQ 7";
+        assert_eq!(rules.total_winnings(input), Ok(5 + 7 * 2 + 3 * 3));
+        assert_eq!(rules.stream_total_winnings(input.as_bytes()).unwrap(), 28);
+    }
+
+    #[test]
+    fn test_other_variants() {
+        // Two wild cards in a six card game
+        let rules = Rules::new("*J23456789TQKA", Some('*'), 6);
+        assert_eq!(rules.score("22*J33"), Score::FullHouse);
+        assert_eq!(rules.score("2*3*4J"), Score::ThreeOfAKind);
+        assert_eq!(rules.score("222333"), Score::FullHouse);
+        assert_eq!(rules.score("2223*4"), Score::FourOfAKind);
+        assert_eq!(rules.score("22*2*3"), Score::FiveOfAKind);
+        assert_eq!(
+            rules.process_input(vec!["22*J33 10", "23456"]).err(),
+            Some(ParseError::MissingToken {
+                line: 2,
+                column: 6,
+                expected: "a bet",
+            })
+        );
+        assert_eq!(
+            rules.process_input(vec!["22*J3 10"]).err(),
+            Some(ParseError::UnexpectedToken {
+                line: 1,
+                column: 1,
+                token: String::from("22*J3"),
+                expected: "a hand with the right number of cards",
+            })
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use crate::part1::{Direction, Network};
+use std::collections::HashMap;
+
+// The walk of a single ghost. Since the next node only depends on the
+// current node and where we are in the instructions, the walk has to repeat
+// once the same (node, instruction index) pair shows up twice.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    // Steps taken before the walk enters the cycle
+    pub start: usize,
+    pub length: usize,
+    // Steps before the cycle that end on a Z node, these never happen again
+    pub tail_hits: Vec<usize>,
+    // Steps of the first time around the cycle that end on a Z node, they
+    // happen again every `length` steps
+    pub cycle_hits: Vec<usize>,
+}
+
+impl Cycle {
+    // Whether the ghost is on a Z node after `step` steps
+    pub fn hits(&self, step: u128) -> bool {
+        if step < self.start as u128 {
+            return self.tail_hits.contains(&(step as usize));
+        }
+        let position = self.start + ((step - self.start as u128) % self.length as u128) as usize;
+        self.cycle_hits.contains(&position)
+    }
+}
+
+pub fn find_cycle(directions: &[Direction], nodes: &Network, start: &str) -> Cycle {
+    // The step at which every (node, instruction index) pair was first seen
+    let mut seen: HashMap<(&str, usize), usize> = HashMap::new();
+    let mut hits: Vec<usize> = Vec::new();
+    let mut current_node = start;
+    let mut step = 0;
+
+    loop {
+        let instruction = step % directions.len();
+        if let Some(&first_seen) = seen.get(&(current_node, instruction)) {
+            let (tail_hits, cycle_hits) = hits.iter().partition(|&&hit| hit < first_seen);
+            return Cycle {
+                start: first_seen,
+                length: step - first_seen,
+                tail_hits,
+                cycle_hits,
+            };
+        }
+        seen.insert((current_node, instruction), step);
+        if current_node.ends_with('Z') {
+            hits.push(step);
+        }
+
+        let options = nodes
+            .get(current_node)
+            .expect("process_input makes sure every child is a node");
+        current_node = match directions[instruction] {
+            Direction::Left => options.0,
+            Direction::Right => options.1,
+        };
+        step += 1;
+    }
+}
+
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        return (a, 1, 0);
+    }
+    let (gcd, x, y) = extended_gcd(b, a % b);
+    (gcd, y, x - (a / b) * y)
+}
+
+// Combines x ≡ a (mod m) and x ≡ b (mod n) into a single congruence, the
+// moduli do not need to be coprime. None when no number satisfies both.
+pub fn combine_congruences((a, m): (u128, u128), (b, n): (u128, u128)) -> Option<(u128, u128)> {
+    let (gcd, p, _) = extended_gcd(m as i128, n as i128);
+    let difference = b as i128 - a as i128;
+    if difference % gcd != 0 {
+        return None;
+    }
+    let lcm = m / gcd as u128 * n;
+    // m * p ≡ gcd (mod n) so stepping a by m * p * difference / gcd lands on b
+    let steps = (difference / gcd % (n as i128 / gcd) * p).rem_euclid(n as i128 / gcd);
+    Some(((a + m * steps as u128) % lcm, lcm))
+}
+
+// The first step at which every ghost is on a Z node, None when that never
+// happens
+pub fn first_sync(cycles: &[Cycle]) -> Option<u128> {
+    // A step that is in some ghost's tail is easy to check directly
+    let from_tails = cycles
+        .iter()
+        .flat_map(|cycle| cycle.tail_hits.iter())
+        .map(|&step| step as u128)
+        .filter(|&step| cycles.iter().all(|cycle| cycle.hits(step)))
+        .min();
+
+    // Any other step is inside the cycle of every ghost so it matches one of
+    // each ghost's cycle hits. Every combination of hits is kept as
+    // (residue, modulus, earliest step) since the step can not come before
+    // any of the hits it was built from.
+    let mut classes: Vec<(u128, u128, u128)> = vec![(0, 1, 0)];
+    for cycle in cycles {
+        classes = classes
+            .iter()
+            .flat_map(|&(residue, modulus, earliest)| {
+                cycle.cycle_hits.iter().filter_map(move |&hit| {
+                    let (residue, modulus) = combine_congruences(
+                        (residue, modulus),
+                        (hit as u128 % cycle.length as u128, cycle.length as u128),
+                    )?;
+                    Some((residue, modulus, earliest.max(hit as u128)))
+                })
+            })
+            .collect();
+    }
+    let from_cycles = classes
+        .iter()
+        .map(|&(residue, modulus, earliest)| {
+            if residue >= earliest {
+                residue
+            } else {
+                residue + (earliest - residue).div_ceil(modulus) * modulus
+            }
+        })
+        .min();
+
+    match (from_tails, from_cycles) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combine_congruences() {
+        assert_eq!(combine_congruences((2, 3), (3, 5)), Some((8, 15)));
+        assert_eq!(combine_congruences((2, 4), (4, 6)), Some((10, 12)));
+        assert_eq!(combine_congruences((1, 4), (2, 6)), None);
+        assert_eq!(combine_congruences((0, 1), (5, 7)), Some((5, 7)));
+    }
+
+    #[test]
+    fn test_first_sync() {
+        let cycle = |start, length, tail_hits: Vec<usize>, cycle_hits: Vec<usize>| Cycle {
+            start,
+            length,
+            tail_hits,
+            cycle_hits,
+        };
+        // Two Z nodes per cycle and a tail before it
+        let a = cycle(3, 10, vec![2], vec![4, 7]);
+        let b = cycle(0, 6, vec![], vec![1]);
+        assert_eq!(first_sync(&[a.clone(), b.clone()]), Some(7));
+        let brute_force = (0..1000).find(|&step| a.hits(step) && b.hits(step));
+        assert_eq!(brute_force, Some(7));
+
+        // Only meet in the tail
+        let c = cycle(5, 2, vec![2], vec![]);
+        assert_eq!(first_sync(&[a, c]), Some(2));
+
+        // Always out of step
+        let d = cycle(0, 4, vec![], vec![1]);
+        let e = cycle(0, 2, vec![], vec![0]);
+        assert_eq!(first_sync(&[d, e]), None);
+    }
+}
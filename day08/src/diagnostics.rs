@@ -0,0 +1,199 @@
+use crate::{
+    part1::{Direction, Network},
+    NavigationError,
+};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+// Tarjan's algorithm, every group of nodes that can all reach each other
+pub fn components<'a>(nodes: &Network<'a>) -> Vec<Vec<&'a str>> {
+    struct State<'a> {
+        index: HashMap<&'a str, usize>,
+        low_link: HashMap<&'a str, usize>,
+        stack: Vec<&'a str>,
+        on_stack: HashSet<&'a str>,
+        components: Vec<Vec<&'a str>>,
+    }
+
+    fn visit<'a>(node: &'a str, nodes: &Network<'a>, state: &mut State<'a>) {
+        let index = state.index.len();
+        state.index.insert(node, index);
+        state.low_link.insert(node, index);
+        state.stack.push(node);
+        state.on_stack.insert(node);
+
+        let (left, right) = nodes[node];
+        for child in [left, right] {
+            if !state.index.contains_key(child) {
+                visit(child, nodes, state);
+                let low_link = state.low_link[node].min(state.low_link[child]);
+                state.low_link.insert(node, low_link);
+            } else if state.on_stack.contains(child) {
+                let low_link = state.low_link[node].min(state.index[child]);
+                state.low_link.insert(node, low_link);
+            }
+        }
+
+        // The node is the root of a component, everything above it on the
+        // stack belongs to it
+        if state.low_link[node] == state.index[node] {
+            let mut component = Vec::new();
+            while let Some(member) = state.stack.pop() {
+                state.on_stack.remove(member);
+                component.push(member);
+                if member == node {
+                    break;
+                }
+            }
+            component.sort();
+            state.components.push(component);
+        }
+    }
+
+    let mut state = State {
+        index: HashMap::new(),
+        low_link: HashMap::new(),
+        stack: Vec::new(),
+        on_stack: HashSet::new(),
+        components: Vec::new(),
+    };
+    for &node in nodes.keys() {
+        if !state.index.contains_key(node) {
+            visit(node, nodes, &mut state);
+        }
+    }
+    state.components
+}
+
+// Every node that can be reached from `start` taking any turns
+pub fn reachable<'a>(nodes: &Network<'a>, start: &'a str) -> BTreeSet<&'a str> {
+    let mut seen = BTreeSet::from([start]);
+    let mut stack = vec![start];
+    while let Some(node) = stack.pop() {
+        let (left, right) = nodes[node];
+        for child in [left, right] {
+            if seen.insert(child) {
+                stack.push(child);
+            }
+        }
+    }
+    seen
+}
+
+// Makes sure following the instructions from `start` ends on a node for
+// which `is_end` is true, `target` describes those nodes in errors. The walk
+// only depends on the node and the instruction index so it is stuck as soon
+// as one of those pairs repeats.
+pub fn check_walk(
+    directions: &[Direction],
+    nodes: &Network,
+    start: &str,
+    target: &str,
+    is_end: impl Fn(&str) -> bool,
+) -> Result<(), NavigationError> {
+    let Some((&start, _)) = nodes.get_key_value(start) else {
+        return Err(NavigationError::MissingNode(String::from(start)));
+    };
+
+    let reached = reachable(nodes, start);
+    if !reached.iter().any(|node| is_end(node)) {
+        return Err(NavigationError::Unreachable {
+            start: String::from(start),
+            target: String::from(target),
+            reachable: reached.len(),
+        });
+    }
+
+    let mut seen: HashMap<(&str, usize), usize> = HashMap::new();
+    let mut current_node = start;
+    for (step, instruction) in (0..directions.len()).cycle().enumerate() {
+        if is_end(current_node) {
+            return Ok(());
+        }
+        if let Some(&first_seen) = seen.get(&(current_node, instruction)) {
+            // Every node of the loop is in the same component, report how
+            // big the part of the network the walk is trapped in is
+            let component = components(nodes)
+                .into_iter()
+                .find(|component| component.contains(&current_node))
+                .expect("every node is in a component");
+            return Err(NavigationError::Stuck {
+                start: String::from(start),
+                target: String::from(target),
+                steps: first_seen,
+                loop_length: step - first_seen,
+                component: component.iter().map(|node| node.to_string()).collect(),
+            });
+        }
+        seen.insert((current_node, instruction), step);
+        let (left, right) = nodes[current_node];
+        current_node = match directions[instruction] {
+            Direction::Left => left,
+            Direction::Right => right,
+        };
+    }
+    unreachable!("cycling over the instructions never ends")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part1::process_input;
+
+    #[test]
+    fn test_components() {
+        let (_, nodes) = process_input(vec![
+            "L",
+            "",
+            "AAA = (BBB, CCC)",
+            "BBB = (AAA, DDD)",
+            "CCC = (DDD, DDD)",
+            "DDD = (DDD, CCC)",
+        ])
+        .unwrap();
+        let mut components = components(&nodes);
+        components.sort();
+        assert_eq!(components, vec![vec!["AAA", "BBB"], vec!["CCC", "DDD"]]);
+        assert_eq!(reachable(&nodes, "CCC").len(), 2);
+    }
+
+    #[test]
+    fn test_check_walk() {
+        let (directions, nodes) = process_input(vec![
+            "L",
+            "",
+            "AAA = (BBB, ZZZ)",
+            "BBB = (AAA, CCC)",
+            "CCC = (CCC, CCC)",
+            "ZZZ = (ZZZ, ZZZ)",
+        ])
+        .unwrap();
+        let is_zzz = |node: &str| node == "ZZZ";
+        // ZZZ is only a right turn away but the instructions only go left
+        assert_eq!(
+            check_walk(&directions, &nodes, "AAA", "ZZZ", is_zzz),
+            Err(NavigationError::Stuck {
+                start: String::from("AAA"),
+                target: String::from("ZZZ"),
+                steps: 0,
+                loop_length: 2,
+                component: vec![String::from("AAA"), String::from("BBB")],
+            })
+        );
+        assert_eq!(
+            check_walk(&directions, &nodes, "CCC", "ZZZ", is_zzz),
+            Err(NavigationError::Unreachable {
+                start: String::from("CCC"),
+                target: String::from("ZZZ"),
+                reachable: 1,
+            })
+        );
+        assert_eq!(
+            check_walk(&directions, &nodes, "XXX", "ZZZ", is_zzz),
+            Err(NavigationError::MissingNode(String::from("XXX")))
+        );
+        assert_eq!(
+            check_walk(&directions, &nodes, "ZZZ", "ZZZ", is_zzz),
+            Ok(())
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use crate::part1::{Direction, Network};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::Write,
+};
+
+// Fill colors for the nodes ghosts start and end on
+const START_COLOR: &str = "palegreen";
+const END_COLOR: &str = "salmon";
+
+// Graphviz description of the network with every edge labelled L or R.
+// Edges taken along `path` following `directions` are drawn in bold red.
+// Both edges of a node with the same child on each side are always drawn
+// but only the side actually taken is highlighted.
+pub fn to_dot(nodes: &Network, directions: &[Direction], path: &[&str]) -> String {
+    let walked: HashSet<(&str, Direction)> = path
+        .windows(2)
+        .zip(directions.iter().cycle())
+        .map(|(step, &direction)| (step[0], direction))
+        .collect();
+
+    let mut dot = String::from("digraph network {\n");
+    for node in nodes.keys() {
+        let color = if node.ends_with('A') {
+            Some(START_COLOR)
+        } else if node.ends_with('Z') {
+            Some(END_COLOR)
+        } else {
+            None
+        };
+        if let Some(color) = color {
+            writeln!(dot, "    \"{}\" [style=filled, fillcolor={}];", node, color).unwrap();
+        }
+    }
+    for (node, (left, right)) in nodes {
+        for (label, direction, child) in
+            [("L", Direction::Left, left), ("R", Direction::Right, right)]
+        {
+            let highlight = if walked.contains(&(*node, direction)) {
+                ", color=red, penwidth=2"
+            } else {
+                ""
+            };
+            writeln!(
+                dot,
+                "    \"{}\" -> \"{}\" [label=\"{}\"{}];",
+                node, child, label, highlight
+            )
+            .unwrap();
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+// Every node with its children as {"AAA": {"L": "BBB", "R": "CCC"}}
+pub fn to_json(nodes: &Network) -> String {
+    let adjacency: BTreeMap<&str, BTreeMap<&str, &str>> = nodes
+        .iter()
+        .map(|(&node, &(left, right))| (node, BTreeMap::from([("L", left), ("R", right)])))
+        .collect();
+    serde_json::to_string_pretty(&adjacency).expect("adjacency only holds strings")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part1::{process_input, walk};
+
+    #[test]
+    fn test_export() {
+        let (directions, nodes) = process_input(vec![
+            "LLR",
+            "",
+            "AAA = (BBB, BBB)",
+            "BBB = (AAA, ZZZ)",
+            "ZZZ = (ZZZ, ZZZ)",
+        ])
+        .unwrap();
+        let path = walk(&directions, &nodes).unwrap();
+        assert_eq!(
+            to_dot(&nodes, &directions, &path),
+            "digraph network {
+    \"AAA\" [style=filled, fillcolor=palegreen];
+    \"ZZZ\" [style=filled, fillcolor=salmon];
+    \"AAA\" -> \"BBB\" [label=\"L\", color=red, penwidth=2];
+    \"AAA\" -> \"BBB\" [label=\"R\", color=red, penwidth=2];
+    \"BBB\" -> \"AAA\" [label=\"L\", color=red, penwidth=2];
+    \"BBB\" -> \"ZZZ\" [label=\"R\", color=red, penwidth=2];
+    \"ZZZ\" -> \"ZZZ\" [label=\"L\"];
+    \"ZZZ\" -> \"ZZZ\" [label=\"R\"];
+}
+"
+        );
+
+        // Going straight through only takes the left side out of AAA
+        let (directions, nodes) = process_input(vec![
+            "LR",
+            "",
+            "AAA = (BBB, BBB)",
+            "BBB = (AAA, ZZZ)",
+            "ZZZ = (ZZZ, ZZZ)",
+        ])
+        .unwrap();
+        let path = walk(&directions, &nodes).unwrap();
+        let dot = to_dot(&nodes, &directions, &path);
+        assert!(dot.contains("\"AAA\" -> \"BBB\" [label=\"L\", color=red, penwidth=2];"));
+        assert!(dot.contains("\"AAA\" -> \"BBB\" [label=\"R\"];"));
+        assert!(dot.contains("\"BBB\" -> \"AAA\" [label=\"L\"];"));
+        assert!(dot.contains("\"BBB\" -> \"ZZZ\" [label=\"R\", color=red, penwidth=2];"));
+
+        let json: serde_json::Value = serde_json::from_str(&to_json(&nodes)).unwrap();
+        assert_eq!(json["BBB"]["L"], "AAA");
+        assert_eq!(json["BBB"]["R"], "ZZZ");
+        assert_eq!(json.as_object().unwrap().len(), 3);
+    }
+}
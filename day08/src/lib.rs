@@ -1,2 +1,101 @@
+use common::{parse::ParseError, Answer, Solution};
+use std::{error::Error, fmt};
+
+pub mod cycle;
+pub mod diagnostics;
+pub mod export;
 pub mod part1;
 pub mod part2;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NavigationError {
+    Parse(ParseError),
+    // A walk starts on a node the network does not have
+    MissingNode(String),
+    // None of the nodes the start can reach in any way are a target
+    Unreachable {
+        start: String,
+        target: String,
+        reachable: usize,
+    },
+    // A target can be reached but following the instructions goes around a
+    // loop of `loop_length` steps, starting after `steps` steps, forever.
+    // `component` is every node that can reach every node of the loop.
+    Stuck {
+        start: String,
+        target: String,
+        steps: usize,
+        loop_length: usize,
+        component: Vec<String>,
+    },
+    // There are no nodes ending in A for the ghosts to start on
+    NoStartingNodes,
+    // The ghosts are never all on a Z node at the same time
+    NoSolution,
+}
+
+impl fmt::Display for NavigationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NavigationError::Parse(err) => write!(f, "{}", err),
+            NavigationError::MissingNode(node) => write!(f, "there is no node {}", node),
+            NavigationError::Unreachable {
+                start,
+                target,
+                reachable,
+            } => write!(
+                f,
+                "{} can never reach {}, none of the {} nodes it can reach are",
+                start, target, reachable
+            ),
+            NavigationError::Stuck {
+                start,
+                target,
+                steps,
+                loop_length,
+                component,
+            } => write!(
+                f,
+                "{} never reaches {} following the instructions, after {} steps it repeats a \
+                 loop of {} steps inside the nodes {}",
+                start,
+                target,
+                steps,
+                loop_length,
+                component.join(", ")
+            ),
+            NavigationError::NoStartingNodes => write!(f, "no nodes end in A"),
+            NavigationError::NoSolution => {
+                write!(f, "no solution, the ghosts never reach Z nodes together")
+            }
+        }
+    }
+}
+
+impl Error for NavigationError {}
+
+impl From<ParseError> for NavigationError {
+    fn from(err: ParseError) -> Self {
+        NavigationError::Parse(err)
+    }
+}
+
+pub struct Day08;
+
+impl Solution for Day08 {
+    fn day(&self) -> u8 {
+        8
+    }
+
+    fn title(&self) -> &'static str {
+        "Haunted Wasteland"
+    }
+
+    fn part_one(&self, input: &str) -> Result<Answer, Box<dyn Error>> {
+        part1::part_one(input).map(Answer::from).map_err(Into::into)
+    }
+
+    fn part_two(&self, input: &str) -> Option<Result<Answer, Box<dyn Error>>> {
+        Some(part2::part_two(input).map(Answer::from).map_err(Into::into))
+    }
+}
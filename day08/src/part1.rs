@@ -1,68 +1,93 @@
+use crate::{diagnostics::check_walk, NavigationError};
+use common::parse::{Line, ParseError};
 use std::collections::BTreeMap;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Direction {
     Left,
     Right,
 }
 
-pub fn process_input(lines: Vec<&str>) -> (Vec<Direction>, BTreeMap<&str, (&str, &str)>) {
-    let mut nodes: BTreeMap<&str, (&str, &str)> = BTreeMap::new();
+// Each node along with its (left, right) children
+pub type Network<'a> = BTreeMap<&'a str, (&'a str, &'a str)>;
 
-    let directions: Vec<Direction> = lines[0]
+pub fn process_input(lines: Vec<&str>) -> Result<(Vec<Direction>, Network<'_>), ParseError> {
+    let mut nodes: Network = BTreeMap::new();
+
+    let direction_line = Line::new(0, lines.first().ok_or(ParseError::EmptyInput)?);
+    let directions: Vec<Direction> = direction_line
+        .text
         .chars()
-        .collect::<Vec<char>>()
-        .iter()
-        .map(|c| match c {
-            'L' => Direction::Left,
-            'R' => Direction::Right,
-            _ => panic!("Invalid direction {}", c),
+        .enumerate()
+        .map(|(index, c)| match c {
+            'L' => Ok(Direction::Left),
+            'R' => Ok(Direction::Right),
+            _ => Err(direction_line.unexpected_char(index, c, "L or R")),
         })
-        .collect::<Vec<Direction>>();
-    lines.iter().skip(2).for_each(|line| {
-        let (node, children) = line.split_once(" = ").unwrap();
-        let children = children
-            .split(", ")
-            .map(|child| child.trim_matches(|c| c == '(' || c == ')'))
-            .collect::<Vec<&str>>();
-        nodes.insert(node, (children[0], children[1]));
-    });
-    (directions, nodes)
-}
-
-pub fn part_one(input: &str) -> usize {
-    let mut lines = input.split("\n").collect::<Vec<&str>>();
-    if lines[lines.len() - 1] == "" {
-        lines.pop();
+        .collect::<Result<Vec<Direction>, ParseError>>()?;
+    if directions.is_empty() {
+        return Err(direction_line.missing("a direction"));
     }
-    let (directions, nodes) = process_input(lines);
 
-    let mut current_node = "AAA";
-    let count: Option<usize> = directions
+    let node_lines: Vec<Line> = lines
         .iter()
-        .cycle()
         .enumerate()
-        .find_map(|(index, dir)| {
-            if current_node == "ZZZ" {
-                return Some(index);
-            }
-            let node = nodes.get(current_node).expect("node is valid");
-            match dir {
-                Direction::Left => {
-                    current_node = node.0;
-                },
-                Direction::Right => {
-                    current_node = node.1;
-                }
-            }
-            None
-        });
+        .skip(2)
+        .map(|(index, &text)| Line::new(index, text))
+        .collect();
+    let mut children_tokens: Vec<(Line, &str)> = Vec::new();
+    for line in &node_lines {
+        let (node, children) = line.split_once(line.text, " = ", "a node")?;
+        let (left, right) = line.split_once(children, ", ", "a right child")?;
+        let left = left
+            .strip_prefix('(')
+            .ok_or_else(|| line.unexpected(left, "("))?;
+        let right = right.strip_suffix(')').ok_or_else(|| line.missing(")"))?;
+        nodes.insert(node, (left, right));
+        children_tokens.push((*line, left));
+        children_tokens.push((*line, right));
+    }
 
-    if count.is_none() {
-        panic!("count is none");
+    // Every child needs its own line so walking the network can never get
+    // stuck on a node that does not exist
+    if let Some((line, child)) = children_tokens
+        .iter()
+        .find(|(_, child)| !nodes.contains_key(child))
+    {
+        return Err(line.unexpected(child, "a known node"));
     }
+    Ok((directions, nodes))
+}
 
-    count.unwrap()
+// Every node visited going from AAA to ZZZ, both included
+pub fn walk<'a>(
+    directions: &[Direction],
+    nodes: &Network<'a>,
+) -> Result<Vec<&'a str>, NavigationError> {
+    check_walk(directions, nodes, "AAA", "ZZZ", |node| node == "ZZZ")?;
+    let mut current_node = "AAA";
+    let mut path = vec![current_node];
+    for dir in directions.iter().cycle() {
+        if current_node == "ZZZ" {
+            break;
+        }
+        let node = nodes.get(current_node).expect("node is valid");
+        current_node = match dir {
+            Direction::Left => node.0,
+            Direction::Right => node.1,
+        };
+        path.push(current_node);
+    }
+    Ok(path)
+}
+
+pub fn part_one(input: &str) -> Result<usize, NavigationError> {
+    let mut lines = input.split("\n").collect::<Vec<&str>>();
+    if lines[lines.len() - 1].is_empty() {
+        lines.pop();
+    }
+    let (directions, nodes) = process_input(lines)?;
+    Ok(walk(&directions, &nodes)?.len() - 1)
 }
 
 #[cfg(test)]
@@ -84,7 +109,31 @@ ZZZ = (ZZZ, ZZZ)";
 AAA = (BBB, BBB)
 BBB = (AAA, ZZZ)
 ZZZ = (ZZZ, ZZZ)";
-        assert_eq!(part_one(input_one), 2);
-        assert_eq!(part_one(input_two), 6);
+        assert_eq!(part_one(input_one), Ok(2));
+        assert_eq!(part_one(input_two), Ok(6));
+    }
+
+    #[test]
+    fn test_process_input_error() {
+        let input = vec!["LRX", "", "AAA = (BBB, BBB)"];
+        assert_eq!(
+            process_input(input).err(),
+            Some(ParseError::UnexpectedToken {
+                line: 1,
+                column: 3,
+                token: String::from("X"),
+                expected: "L or R",
+            })
+        );
+        let input = vec!["LR", "", "AAA = (BBB, ZZZ)", "ZZZ = (ZZZ, ZZZ)"];
+        assert_eq!(
+            process_input(input).err(),
+            Some(ParseError::UnexpectedToken {
+                line: 3,
+                column: 8,
+                token: String::from("BBB"),
+                expected: "a known node",
+            })
+        );
     }
 }
@@ -1,99 +1,36 @@
-use std::collections::BTreeMap;
+use crate::{
+    cycle::{find_cycle, first_sync, Cycle},
+    diagnostics::check_walk,
+    part1::process_input,
+    NavigationError,
+};
 
-#[derive(Debug, Clone, Copy)]
-pub enum Direction {
-    Left,
-    Right,
-}
-
-pub fn process_input(lines: Vec<&str>) -> (Vec<Direction>, BTreeMap<&str, (&str, &str)>) {
-    let mut nodes: BTreeMap<&str, (&str, &str)> = BTreeMap::new();
-
-    let directions: Vec<Direction> = lines[0]
-        .chars()
-        .collect::<Vec<char>>()
-        .iter()
-        .map(|c| match c {
-            'L' => Direction::Left,
-            'R' => Direction::Right,
-            _ => panic!("Invalid direction {}", c),
-        })
-        .collect::<Vec<Direction>>();
-    lines.iter().skip(2).for_each(|line| {
-        let (node, children) = line.split_once(" = ").unwrap();
-        let children = children
-            .split(", ")
-            .map(|child| child.trim_matches(|c| c == '(' || c == ')'))
-            .collect::<Vec<&str>>();
-        nodes.insert(node, (children[0], children[1]));
-    });
-    (directions, nodes)
-}
-
-pub fn lcm(nums: &[usize]) -> usize {
-    if nums.len() == 1 {
-        return nums[0];
-    }
-    let a = nums[0];
-    let b = lcm(&nums[1..]);
-    a * b / gcd_of_two_numbers(a, b)
-}
-
-fn gcd_of_two_numbers(a: usize, b: usize) -> usize {
-    if b == 0 {
-        return a;
-    }
-    gcd_of_two_numbers(b, a % b)
-}
-
-pub fn part_two(input: &str) -> usize {
+pub fn part_two(input: &str) -> Result<u128, NavigationError> {
     let mut lines = input.split("\n").collect::<Vec<&str>>();
-    if lines[lines.len() - 1] == "" {
+    if lines[lines.len() - 1].is_empty() {
         lines.pop();
     }
-    let (directions, nodes) = process_input(lines);
-let starting_nodes: Vec<&str> = nodes
+    let (directions, nodes) = process_input(lines)?;
+    let starting_nodes: Vec<&str> = nodes
         .keys()
-        .filter(|key| key.ends_with("A"))
+        .filter(|key| key.ends_with('A'))
         .cloned()
         .collect();
+    if starting_nodes.is_empty() {
+        return Err(NavigationError::NoStartingNodes);
+    }
+    for node in &starting_nodes {
+        check_walk(&directions, &nodes, node, "a node ending in Z", |node| {
+            node.ends_with('Z')
+        })?;
+    }
 
-    let results = starting_nodes
+    let cycles: Vec<Cycle> = starting_nodes
         .iter()
-        .map(|node| {
-            let mut visited_nodes = vec![*node];
-            let mut current_node = *node;
-            // cycle is magically "the first Z",
-            // and other assorted conditions due
-            // to how the input is constructed.
-            directions
-                .iter()
-                .cycle()
-                .enumerate()
-                .find_map(|(index, instruction)| {
-                    let options =
-                        nodes.get(current_node).expect(
-                            "always exist at a valid node",
-                        );
-                    let next_node = match instruction {
-                        Direction::Left => options.0,
-                        Direction::Right => options.1,
-                    };
-                    if next_node.ends_with("Z") {
-                        Some(index + 1)
-                    } else {
-                        visited_nodes.push(next_node);
-                        current_node = next_node;
-                        None
-                    }
-                })
-                .expect("should find a cycle")
-        })
-        .collect::<Vec<usize>>();
-
-    let min_cycle = lcm(&results);
+        .map(|node| find_cycle(&directions, &nodes, node))
+        .collect();
 
-    min_cycle
+    first_sync(&cycles).ok_or(NavigationError::NoSolution)
 }
 
 #[cfg(test)]
@@ -111,6 +48,37 @@ mod tests {
 22C = (22Z, 22Z)
 22Z = (22B, 22B)
 XXX = (XXX, XXX)";
-        assert_eq!(part_two(input_one), 6);
+        assert_eq!(part_two(input_one), Ok(6));
+    }
+
+    #[test]
+    fn test_part_two_out_of_step() {
+        // 11A is on 11Z every other step from step 1 and 22A every other step
+        // from step 2
+        let input = "L
+
+11A = (11Z, 11Z)
+11Z = (11B, 11B)
+11B = (11Z, 11Z)
+22A = (22B, 22B)
+22B = (22Z, 22Z)
+22Z = (22B, 22B)";
+        assert_eq!(part_two(input), Err(NavigationError::NoSolution));
+    }
+
+    #[test]
+    fn test_part_two_unreachable() {
+        let input = "LR
+
+11A = (11Z, 11Z)
+11Z = (11Z, 11Z)
+22A = (22B, 22B)
+22B = (22A, 22A)";
+        assert_eq!(
+            part_two(input).map_err(|err| err.to_string()),
+            Err(String::from(
+                "22A can never reach a node ending in Z, none of the 2 nodes it can reach are"
+            ))
+        );
     }
 }
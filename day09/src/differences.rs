@@ -0,0 +1,85 @@
+use crate::number::Number;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableError {
+    // A difference or prediction does not fit in the number type
+    Overflow,
+    // There were no readings to take the differences of
+    NeverStabilises,
+}
+
+// Every row of differences down to the first one whose values are all the
+// same, which at the latest is the row with a single value left
+pub fn difference_table<N: Number>(readings: &[N]) -> Result<Vec<Vec<N>>, TableError> {
+    let mut rows: Vec<Vec<N>> = vec![readings.to_vec()];
+    loop {
+        let row = rows.last().expect("starts with the readings");
+        if row.is_empty() {
+            return Err(TableError::NeverStabilises);
+        }
+        if row.iter().all(|value| *value == row[0]) {
+            return Ok(rows);
+        }
+        let differences = row
+            .windows(2)
+            .map(|pair| pair[1].checked_sub(&pair[0]))
+            .collect::<Option<Vec<N>>>()
+            .ok_or(TableError::Overflow)?;
+        rows.push(differences);
+    }
+}
+
+// Extends every row of the table by one value at the end
+pub fn predict_next<N: Number>(readings: &[N]) -> Result<N, TableError> {
+    let rows = difference_table(readings)?;
+    let mut rows = rows.iter().rev();
+    let stable = rows.next().expect("there is at least one row")[0].clone();
+    rows.try_fold(stable, |prediction, row| {
+        row[row.len() - 1].checked_add(&prediction)
+    })
+    .ok_or(TableError::Overflow)
+}
+
+// Extends every row of the table by one value at the start
+pub fn predict_previous<N: Number>(readings: &[N]) -> Result<N, TableError> {
+    let rows = difference_table(readings)?;
+    let mut rows = rows.iter().rev();
+    let stable = rows.next().expect("there is at least one row")[0].clone();
+    rows.try_fold(stable, |prediction, row| row[0].checked_sub(&prediction))
+        .ok_or(TableError::Overflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_predict() {
+        assert_eq!(predict_next(&[10_i64, 13, 16, 21, 30, 45]), Ok(68));
+        assert_eq!(predict_previous(&[10_i64, 13, 16, 21, 30, 45]), Ok(5));
+        assert_eq!(predict_next(&[7_i64, 7]), Ok(7));
+        // Too few readings to settle on equal values still stop at one
+        assert_eq!(predict_next(&[1_i64, 2]), Ok(3));
+        assert_eq!(predict_next(&[0_i64, 0, 1]), Ok(3));
+        assert_eq!(predict_previous(&[0_i64, 0, 1]), Ok(1));
+        assert_eq!(predict_next(&[5_i64]), Ok(5));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(predict_next::<i64>(&[]), Err(TableError::NeverStabilises));
+        assert_eq!(
+            predict_next(&[i64::MAX - 2, i64::MAX - 1, i64::MAX]),
+            Err(TableError::Overflow)
+        );
+        assert_eq!(
+            predict_next(&[i64::MIN, 0, i64::MAX]),
+            Err(TableError::Overflow)
+        );
+        // Fine once the numbers are wider
+        assert_eq!(
+            predict_next(&[i64::MAX as i128 - 2, i64::MAX as i128 - 1, i64::MAX as i128]),
+            Ok(i64::MAX as i128 + 1)
+        );
+    }
+}
@@ -1,3 +1,85 @@
+use common::{parse::ParseError, Answer, Solution};
+use number::Number;
+use std::{error::Error, fmt, io::BufRead};
+
+pub mod differences;
+pub mod number;
 pub mod part1;
 pub mod part2;
+pub mod polynomial;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SensorError {
+    Parse(ParseError),
+    // A difference or prediction of the sensor on this line does not fit
+    // in the number type
+    Overflow { line: usize },
+    // The sensor on this line has no readings to take differences of
+    NeverStabilises { line: usize },
+}
+
+impl fmt::Display for SensorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SensorError::Parse(err) => write!(f, "{}", err),
+            SensorError::Overflow { line } => write!(
+                f,
+                "line {}: prediction overflows, try the i128 or bigint feature",
+                line
+            ),
+            SensorError::NeverStabilises { line } => {
+                write!(f, "line {}: there are no readings to predict from", line)
+            }
+        }
+    }
+}
+
+impl Error for SensorError {}
+
+impl From<ParseError> for SensorError {
+    fn from(err: ParseError) -> Self {
+        SensorError::Parse(err)
+    }
+}
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    fn day(&self) -> u8 {
+        9
+    }
+
+    fn title(&self) -> &'static str {
+        "Mirage Maintenance"
+    }
+
+    fn part_one(&self, input: &str) -> Result<Answer, Box<dyn Error>> {
+        part1::part_one(input)
+            .map(Number::into_answer)
+            .map_err(Into::into)
+    }
+
+    fn part_two(&self, input: &str) -> Option<Result<Answer, Box<dyn Error>>> {
+        Some(
+            part2::part_two(input)
+                .map(Number::into_answer)
+                .map_err(Into::into),
+        )
+    }
+
+    fn stream_part_one(&self, reader: &mut dyn BufRead) -> Option<Result<Answer, Box<dyn Error>>> {
+        Some(
+            part1::stream_part_one(reader)
+                .map(Number::into_answer)
+                .map_err(Into::into),
+        )
+    }
 
+    fn stream_part_two(&self, reader: &mut dyn BufRead) -> Option<Result<Answer, Box<dyn Error>>> {
+        Some(
+            part2::stream_part_two(reader)
+                .map(Number::into_answer)
+                .map_err(Into::into),
+        )
+    }
+}
@@ -0,0 +1,65 @@
+use common::Answer;
+use std::{fmt::Debug, str::FromStr};
+
+// Integer type the difference tables are built from
+pub trait Number: Clone + Debug + PartialEq + FromStr {
+    fn checked_add(&self, other: &Self) -> Option<Self>;
+    fn checked_sub(&self, other: &Self) -> Option<Self>;
+    // None when the value does not fit, only used to fit polynomials
+    fn to_i128(&self) -> Option<i128>;
+    fn into_answer(self) -> Answer;
+}
+
+macro_rules! impl_number {
+    ($($t:ty),*) => {
+        $(impl Number for $t {
+            fn checked_add(&self, other: &Self) -> Option<Self> {
+                <$t>::checked_add(*self, *other)
+            }
+
+            fn checked_sub(&self, other: &Self) -> Option<Self> {
+                <$t>::checked_sub(*self, *other)
+            }
+
+            fn to_i128(&self) -> Option<i128> {
+                Some(*self as i128)
+            }
+
+            fn into_answer(self) -> Answer {
+                Answer::from(self)
+            }
+        })*
+    };
+}
+
+impl_number!(i64, i128);
+
+#[cfg(feature = "bigint")]
+impl Number for num_bigint::BigInt {
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(self + other)
+    }
+
+    fn checked_sub(&self, other: &Self) -> Option<Self> {
+        Some(self - other)
+    }
+
+    fn to_i128(&self) -> Option<i128> {
+        i128::try_from(self).ok()
+    }
+
+    fn into_answer(self) -> Answer {
+        match i128::try_from(&self) {
+            Ok(value) => Answer::from(value),
+            Err(_) => Answer::from(self.to_string()),
+        }
+    }
+}
+
+// The backend picked by the enabled features
+#[cfg(feature = "bigint")]
+pub type Value = num_bigint::BigInt;
+#[cfg(all(feature = "i128", not(feature = "bigint")))]
+pub type Value = i128;
+#[cfg(not(any(feature = "i128", feature = "bigint")))]
+pub type Value = i64;
@@ -1,69 +1,119 @@
+use crate::{
+    differences::{self, TableError},
+    number::{Number, Value},
+    polynomial::{Polynomial, Rational},
+    SensorError,
+};
+use common::{
+    parse::{Line, ParseError},
+    read::{fold_lines, ReadError},
+};
+use std::io::BufRead;
+
 #[derive(Debug, Clone)]
 pub struct Sensor {
-    readings: Vec<i64>,
+    // Line of the input the readings came from
+    line: usize,
+    readings: Vec<Value>,
 }
 
 impl Sensor {
-    fn new(readings: Vec<i64>) -> Self {
-        Sensor { readings }
+    fn new(line: usize, readings: Vec<Value>) -> Self {
+        Sensor { line, readings }
     }
 
-    fn are_differences_stable(&self, readings: &Vec<i64>) -> bool {
-        let current = readings[0];
-        for i in 0..readings.len() {
-            if readings[i] != current {
-                return false;
-            }
-        }
-        true
+    // Goes through every reading with reading i at x = i, None when the
+    // readings or the coefficients do not fit in an i128
+    pub fn polynomial(&self) -> Option<Polynomial> {
+        let readings = self
+            .readings
+            .iter()
+            .map(|reading| reading.to_i128())
+            .collect::<Option<Vec<i128>>>()?;
+        Polynomial::fit(&readings)
     }
 
-    fn get_differences(&self, readings: &Vec<i64>) -> Vec<i64> {
-        readings.windows(2).map(|r| r[1] - r[0]).collect()
+    // Value `offset` steps after the last reading, negative offsets go back
+    // from it so -(number of readings) is one step before the first reading
+    pub fn predict(&self, offset: i64) -> Option<Rational> {
+        let last = self.readings.len() as i128 - 1;
+        self.polynomial()?.evaluate(last + offset as i128)
     }
 
-    fn predict_next(&self) -> i64 {
-        let mut stages: Vec<Vec<i64>> = Vec::new();
-        let mut readings = self.readings.clone();
-        // We need to see if all the differences are the same
-        while !self.are_differences_stable(&readings) {
-            // Update stages with the current reading
-            stages.push(readings.clone());
-            // Update readings
-            readings = self.get_differences(&readings);
+    fn error(&self, err: TableError) -> SensorError {
+        match err {
+            TableError::Overflow => SensorError::Overflow { line: self.line },
+            TableError::NeverStabilises => SensorError::NeverStabilises { line: self.line },
         }
-        stages.push(readings);
-        stages.reverse();
-        let mut prediction: i64 = 0;
-        stages.iter().for_each(|stage| {
-            prediction += stage.last().unwrap();
-        });
-        prediction
     }
+
+    pub fn predict_next(&self) -> Result<Value, SensorError> {
+        differences::predict_next(&self.readings).map_err(|err| self.error(err))
+    }
+
+    // Value one step before the first reading
+    pub fn predict_previous(&self) -> Result<Value, SensorError> {
+        differences::predict_previous(&self.readings).map_err(|err| self.error(err))
+    }
+}
+
+pub fn parse_sensor(line: &Line) -> Result<Sensor, ParseError> {
+    let readings = line.parse_all::<Value>(line.text)?;
+    if readings.is_empty() {
+        return Err(line.missing("a reading"));
+    }
+    Ok(Sensor::new(line.number, readings))
 }
 
-pub fn process_input(lines: Vec<&str>) -> Vec<Sensor> {
+pub fn process_input(lines: Vec<&str>) -> Result<Vec<Sensor>, ParseError> {
     lines
         .iter()
-        .map(|&sensor| {
-            Sensor::new(
-                sensor
-                    .split(" ")
-                    .map(|s| s.parse::<i64>().unwrap())
-                    .collect(),
-            )
-        })
+        .enumerate()
+        .map(|(index, &sensor)| parse_sensor(&Line::new(index, sensor)))
         .collect()
 }
 
-pub fn part_one(input: &str) -> i64 {
+fn add_prediction<F>(total: Value, sensor: &Sensor, predict: F) -> Result<Value, SensorError>
+where
+    F: Fn(&Sensor) -> Result<Value, SensorError>,
+{
+    Number::checked_add(&total, &predict(sensor)?)
+        .ok_or(SensorError::Overflow { line: sensor.line })
+}
+
+// Adds up a prediction of every sensor without overflowing
+pub fn sum_predictions<F>(sensors: &[Sensor], predict: F) -> Result<Value, SensorError>
+where
+    F: Fn(&Sensor) -> Result<Value, SensorError>,
+{
+    sensors.iter().try_fold(Value::from(0), |total, sensor| {
+        add_prediction(total, sensor, &predict)
+    })
+}
+
+// Same as `sum_predictions` reading and predicting one sensor at a time
+pub fn stream_predictions<R, F>(reader: R, predict: F) -> Result<Value, ReadError<SensorError>>
+where
+    R: BufRead,
+    F: Fn(&Sensor) -> Result<Value, SensorError>,
+{
+    fold_lines(reader, Value::from(0), |total, line| {
+        add_prediction(total, &parse_sensor(&line)?, &predict)
+    })
+}
+
+pub fn part_one(input: &str) -> Result<Value, SensorError> {
     let mut lines = input.split("\n").collect::<Vec<&str>>();
-    if lines[lines.len() - 1] == "" {
+    if lines[lines.len() - 1].is_empty() {
         lines.pop();
     }
-    let sensors = process_input(lines);
-    sensors.iter().map(|s| s.predict_next()).sum()
+    let sensors = process_input(lines)?;
+    sum_predictions(&sensors, Sensor::predict_next)
+}
+pub fn stream_part_one<R: BufRead>(reader: R) -> Result<Value, ReadError<SensorError>> {
+    stream_predictions(reader, Sensor::predict_next)
 }
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -72,6 +122,56 @@ mod tests {
         let input_one = "0 3 6 9 12 15
 1 3 6 10 15 21
 10 13 16 21 30 45";
-        assert_eq!(part_one(input_one), 114);
+        assert_eq!(part_one(input_one), Ok(Value::from(114)));
+        assert_eq!(
+            stream_part_one(input_one.as_bytes()).unwrap(),
+            Value::from(114)
+        );
+    }
+
+    #[test]
+    fn test_predict() {
+        let sensor = Sensor::new(1, [10, 13, 16, 21, 30, 45].map(Value::from).to_vec());
+        assert_eq!(sensor.polynomial().unwrap().degree(), Some(3));
+        assert_eq!(sensor.predict(1), Some(Rational::from_integer(68)));
+        assert_eq!(sensor.predict(-6), Some(Rational::from_integer(5)));
+        assert_eq!(sensor.predict(-5), Some(Rational::from_integer(10)));
+        assert_eq!(sensor.predict(3), Some(Rational::from_integer(146)));
+
+        // Differences and the polynomial agree on the next value, even
+        // when the readings run out before the differences are constant
+        for readings in [
+            vec![1, 2],
+            vec![0, 0, 1],
+            vec![5],
+            vec![10, 13, 16, 21, 30, 45],
+        ] {
+            let sensor = Sensor::new(1, readings.into_iter().map(Value::from).collect());
+            let next = sensor.predict_next().unwrap().to_i128().unwrap();
+            assert_eq!(sensor.predict(1), Some(Rational::from_integer(next)));
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            part_one("0 3 6\n\n1 2"),
+            Err(SensorError::Parse(ParseError::MissingToken {
+                line: 2,
+                column: 1,
+                expected: "a reading",
+            }))
+        );
+        assert_eq!(part_one("0 3 6\n1 2"), Ok(Value::from(12)));
+        assert_eq!(part_one("1 2\n0 0 1"), Ok(Value::from(6)));
+    }
+
+    #[cfg(not(any(feature = "i128", feature = "bigint")))]
+    #[test]
+    fn test_overflow() {
+        let input = format!("0 0\n{} {} {}", i64::MAX - 2, i64::MAX - 1, i64::MAX);
+        assert_eq!(part_one(&input), Err(SensorError::Overflow { line: 2 }));
+        let input = format!("{} {}\n1 1", i64::MAX, i64::MAX);
+        assert_eq!(part_one(&input), Err(SensorError::Overflow { line: 2 }));
     }
 }
@@ -0,0 +1,106 @@
+use num_rational::Ratio;
+use num_traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub};
+
+pub type Rational = Ratio<i128>;
+
+// A polynomial c0 + c1 * x + c2 * x^2 + ... with exact rational coefficients
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polynomial {
+    // Lowest power first without trailing zeros so the zero polynomial has
+    // no coefficients at all
+    coefficients: Vec<Rational>,
+}
+
+impl Polynomial {
+    pub fn new(mut coefficients: Vec<Rational>) -> Self {
+        while coefficients.last() == Some(&Rational::from_integer(0)) {
+            coefficients.pop();
+        }
+        Polynomial { coefficients }
+    }
+
+    // The lowest degree polynomial going through (x, values[x]) for every
+    // index x, None when a coefficient does not fit. Uses Newton's forward
+    // differences, value at x is the sum of Δ^k values[0] * (x choose k).
+    pub fn fit(values: &[i128]) -> Option<Self> {
+        let mut coefficients: Vec<Rational> = Vec::new();
+        // (x choose k) as a polynomial in x, starting with (x choose 0) = 1
+        let mut binomial: Vec<Rational> = vec![Rational::from_integer(1)];
+        let mut differences: Vec<i128> = values.to_vec();
+
+        for k in 0..values.len() {
+            let leading = Rational::from_integer(differences[0]);
+            coefficients.resize(binomial.len(), Rational::from_integer(0));
+            for (coefficient, term) in coefficients.iter_mut().zip(&binomial) {
+                *coefficient = coefficient.checked_add(&leading.checked_mul(term)?)?;
+            }
+
+            // (x choose k + 1) = (x choose k) * (x - k) / (k + 1)
+            let k = Rational::from_integer(k as i128);
+            let next_k = k.checked_add(&Rational::from_integer(1))?;
+            let mut next = vec![Rational::from_integer(0); binomial.len() + 1];
+            for (power, term) in binomial.iter().enumerate() {
+                next[power + 1] = next[power + 1].checked_add(&term.checked_div(&next_k)?)?;
+                next[power] =
+                    next[power].checked_sub(&term.checked_mul(&k)?.checked_div(&next_k)?)?;
+            }
+            binomial = next;
+            differences = differences
+                .windows(2)
+                .map(|pair| pair[1].checked_sub(pair[0]))
+                .collect::<Option<Vec<i128>>>()?;
+        }
+
+        Some(Polynomial::new(coefficients))
+    }
+
+    // None for the zero polynomial
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    pub fn coefficients(&self) -> &[Rational] {
+        &self.coefficients
+    }
+
+    // None when the value does not fit
+    pub fn evaluate(&self, x: i128) -> Option<Rational> {
+        let x = Rational::from_integer(x);
+        // Horner's method, starting from the highest power
+        self.coefficients
+            .iter()
+            .rev()
+            .try_fold(Rational::from_integer(0), |value, coefficient| {
+                value.checked_mul(&x)?.checked_add(coefficient)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit() {
+        // x^2 / 2 + 3x / 2 + 1
+        let polynomial = Polynomial::fit(&[1, 3, 6, 10, 15, 21]).unwrap();
+        assert_eq!(polynomial.degree(), Some(2));
+        assert_eq!(
+            polynomial.coefficients(),
+            &[
+                Rational::from_integer(1),
+                Rational::new(3, 2),
+                Rational::new(1, 2)
+            ]
+        );
+        assert_eq!(polynomial.evaluate(6), Some(Rational::from_integer(28)));
+        assert_eq!(polynomial.evaluate(-1), Some(Rational::from_integer(0)));
+        assert_eq!(polynomial.evaluate(-3), Some(Rational::from_integer(1)));
+        assert_eq!(polynomial.evaluate(i128::MAX), None);
+
+        assert_eq!(Polynomial::fit(&[4, 4, 4]).unwrap().degree(), Some(0));
+        assert_eq!(Polynomial::fit(&[0, 0]).unwrap().degree(), None);
+        assert_eq!(Polynomial::fit(&[]).unwrap().degree(), None);
+        assert_eq!(Polynomial::fit(&[i128::MIN, i128::MAX]), None);
+    }
+}
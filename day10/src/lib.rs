@@ -1,2 +1,130 @@
+use common::{parse::ParseError, Answer, Solution};
+use grid::Position;
+use part1::{Direction, TileType};
+use std::{error::Error, fmt};
+
 pub mod part1;
 pub mod part2;
+pub mod render;
+pub mod validate;
+
+// Positions are reported as 1-based lines and columns like parse errors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MazeError {
+    Parse(ParseError),
+    // There is no S to start the loop from
+    NoStart,
+    // More than one S, the loop could start at any of them
+    MultipleStarts(Vec<Position>),
+    // S does not connect to exactly two neighbors and there is not exactly
+    // one pipe it could be that closes the loop. `closes_loop` are the pipes
+    // that do.
+    AmbiguousStart {
+        start: Position,
+        connections: Vec<Direction>,
+        closes_loop: Vec<TileType>,
+    },
+    // A pipe opens towards a tile that does not open back or off the grid
+    DanglingEnd {
+        position: Position,
+        tile: TileType,
+        direction: Direction,
+    },
+    // Pipes connected to each other but not to S, only reported by
+    // `validate::validate` since puzzle inputs are full of them
+    Disconnected(Vec<Position>),
+}
+
+impl fmt::Display for MazeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let at = |(x, y): Position| format!("line {}, column {}", y + 1, x + 1);
+        match self {
+            MazeError::Parse(err) => write!(f, "{}", err),
+            MazeError::NoStart => write!(f, "there is no start tile"),
+            MazeError::MultipleStarts(starts) => write!(
+                f,
+                "there are {} start tiles at {}",
+                starts.len(),
+                starts
+                    .iter()
+                    .map(|&start| at(start))
+                    .collect::<Vec<String>>()
+                    .join("; ")
+            ),
+            MazeError::AmbiguousStart {
+                start,
+                connections,
+                closes_loop,
+            } => {
+                write!(
+                    f,
+                    "{}: the start connects to {} neighbors",
+                    at(*start),
+                    connections.len()
+                )?;
+                if !connections.is_empty() {
+                    let connections: Vec<String> =
+                        connections.iter().map(ToString::to_string).collect();
+                    write!(f, " ({})", connections.join(", "))?;
+                }
+                match &closes_loop[..] {
+                    [] => write!(f, " and no pipe in its place closes the loop"),
+                    pipes => write!(
+                        f,
+                        " and the loop closes with any of {} in its place",
+                        pipes
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<String>>()
+                            .join(" ")
+                    ),
+                }
+            }
+            MazeError::DanglingEnd {
+                position,
+                tile,
+                direction,
+            } => write!(
+                f,
+                "{}: {} opens {} but nothing connects back",
+                at(*position),
+                tile,
+                direction
+            ),
+            MazeError::Disconnected(tiles) => write!(
+                f,
+                "{}: {} pipes are not connected to the start",
+                at(tiles[0]),
+                tiles.len()
+            ),
+        }
+    }
+}
+
+impl Error for MazeError {}
+
+impl From<ParseError> for MazeError {
+    fn from(err: ParseError) -> Self {
+        MazeError::Parse(err)
+    }
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    fn day(&self) -> u8 {
+        10
+    }
+
+    fn title(&self) -> &'static str {
+        "Pipe Maze"
+    }
+
+    fn part_one(&self, input: &str) -> Result<Answer, Box<dyn Error>> {
+        part1::part_one(input).map(Answer::from).map_err(Into::into)
+    }
+
+    fn part_two(&self, input: &str) -> Option<Result<Answer, Box<dyn Error>>> {
+        Some(part2::part_two(input).map(Answer::from).map_err(Into::into))
+    }
+}
@@ -1,4 +1,7 @@
-use std::collections::HashMap;
+use crate::MazeError;
+use common::parse::ParseError;
+use grid::{Grid, Position};
+use std::fmt;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum Direction {
@@ -8,6 +11,46 @@ pub enum Direction {
     West,
 }
 
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    // (dx, dy) of a single step
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::North => (0, -1),
+            Direction::East => (1, 0),
+            Direction::South => (0, 1),
+            Direction::West => (-1, 0),
+        }
+    }
+
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::North => Direction::South,
+            Direction::East => Direction::West,
+            Direction::South => Direction::North,
+            Direction::West => Direction::East,
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Direction::North => "north",
+            Direction::East => "east",
+            Direction::South => "south",
+            Direction::West => "west",
+        };
+        write!(f, "{}", name)
+    }
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum TileType {
     Vertical,
@@ -20,138 +63,176 @@ pub enum TileType {
     Start,
 }
 
-pub fn process_input(lines: Vec<&str>) -> HashMap<(i64, i64), TileType> {
-    let mut map: HashMap<(i64, i64), TileType> = HashMap::new();
-    lines.iter().enumerate().for_each(|(row, line)| {
-        line.chars().enumerate().for_each(|(column, c)| {
-            let tile = match c {
-                '|' => TileType::Vertical,
-                '-' => TileType::Horizontal,
-                'L' => TileType::NorthEast,
-                'J' => TileType::NorthWest,
-                '7' => TileType::SouthWest,
-                'F' => TileType::SouthEast,
-                '.' => TileType::Ground,
-                'S' => TileType::Start,
-                _ => panic!("Invalid character in input"),
-            };
-            if tile != TileType::Ground {
-                map.insert((column as i64, row as i64), tile);
-            }
-        });
-    });
-    map
+impl TileType {
+    pub const PIPES: [TileType; 6] = [
+        TileType::Vertical,
+        TileType::Horizontal,
+        TileType::NorthEast,
+        TileType::NorthWest,
+        TileType::SouthWest,
+        TileType::SouthEast,
+    ];
+
+    fn from_char(c: char) -> Option<TileType> {
+        match c {
+            '|' => Some(TileType::Vertical),
+            '-' => Some(TileType::Horizontal),
+            'L' => Some(TileType::NorthEast),
+            'J' => Some(TileType::NorthWest),
+            '7' => Some(TileType::SouthWest),
+            'F' => Some(TileType::SouthEast),
+            '.' => Some(TileType::Ground),
+            'S' => Some(TileType::Start),
+            _ => None,
+        }
+    }
+
+    // Directions the pipe opens towards, none for ground and the start
+    // since its shape is not known from the tile alone
+    pub fn openings(self) -> &'static [Direction] {
+        match self {
+            TileType::Vertical => &[Direction::North, Direction::South],
+            TileType::Horizontal => &[Direction::East, Direction::West],
+            TileType::NorthEast => &[Direction::North, Direction::East],
+            TileType::NorthWest => &[Direction::North, Direction::West],
+            TileType::SouthWest => &[Direction::South, Direction::West],
+            TileType::SouthEast => &[Direction::South, Direction::East],
+            TileType::Ground | TileType::Start => &[],
+        }
+    }
+
+    // The pipe with exactly these two openings
+    pub fn from_openings(a: Direction, b: Direction) -> Option<TileType> {
+        TileType::PIPES.into_iter().find(|pipe| {
+            let openings = pipe.openings();
+            a != b && openings.contains(&a) && openings.contains(&b)
+        })
+    }
 }
 
-pub fn part_one(input: &str) -> usize {
-    let mut lines = input.split("\n").collect::<Vec<&str>>();
-    if lines[lines.len() - 1] == "" {
-        lines.pop();
+// Shown as the character it has in the input
+impl fmt::Display for TileType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let c = match self {
+            TileType::Vertical => '|',
+            TileType::Horizontal => '-',
+            TileType::NorthEast => 'L',
+            TileType::NorthWest => 'J',
+            TileType::SouthWest => '7',
+            TileType::SouthEast => 'F',
+            TileType::Ground => '.',
+            TileType::Start => 'S',
+        };
+        write!(f, "{}", c)
     }
-    let grid = process_input(lines);
-    let start_position = grid
+}
+
+pub fn process_input(input: &str) -> Result<Grid<TileType>, ParseError> {
+    Grid::parse(input, "a pipe, ground or start tile", TileType::from_char)
+}
+
+pub fn find_start(grid: &Grid<TileType>) -> Result<Position, MazeError> {
+    let starts: Vec<Position> = grid
         .iter()
-        .find_map(|(key, value)| (value == &TileType::Start).then_some(key))
-        .expect("start exists");
-
-    // Up
-    let north: (i64, i64) = (start_position.0, start_position.1 - 1);
-    let north_position = grid
-        .get(&north)
-        .is_some_and(|pipe_type| match pipe_type {
-            TileType::Vertical | TileType::SouthWest | TileType::SouthEast => true,
-            _ => false,
-        })
-        .then_some((Direction::South, north));
-    // Right to go east
-    let east = (start_position.0 + 1, start_position.1);
-    let east_position = grid
-        .get(&east)
-        .is_some_and(|pipe_type| match pipe_type {
-            TileType::Horizontal | TileType::NorthWest | TileType::SouthWest => true,
-            _ => false,
-        })
-        .then_some((Direction::West, east));
-    // Down to go south
-    let south = (start_position.0, start_position.1 + 1);
-    let south_position = grid
-        .get(&south)
-        .is_some_and(|pipe_type| match pipe_type {
-            TileType::Vertical | TileType::NorthWest | TileType::NorthEast => true,
-            _ => false,
-        })
-        .then_some((Direction::North, south));
-    // Left to go west
-    let west = (start_position.0 - 1, start_position.1);
-    let west_position = grid
-        .get(&west)
-        .is_some_and(|pipe_type| match pipe_type {
-            TileType::Horizontal | TileType::NorthEast | TileType::SouthEast => true,
-            _ => false,
+        .filter(|&(_, &tile)| tile == TileType::Start)
+        .map(|(position, _)| position)
+        .collect();
+    match starts[..] {
+        [start] => Ok(start),
+        [] => Err(MazeError::NoStart),
+        _ => Err(MazeError::MultipleStarts(starts)),
+    }
+}
+
+// Directions of the neighbors that open back towards `position`
+pub fn connections(grid: &Grid<TileType>, position: Position) -> Vec<Direction> {
+    Direction::ALL
+        .into_iter()
+        .filter(|direction| {
+            let (dx, dy) = direction.offset();
+            grid.offset(position, dx, dy)
+                .is_some_and(|neighbor| grid[neighbor].openings().contains(&direction.opposite()))
         })
-        .then_some((Direction::East, west));
-    let mut iters = vec![north_position, south_position, east_position, west_position]
+        .collect()
+}
+
+// The pipe hidden under S, it opens towards the neighbors that open back
+// towards it. When there are not exactly two of them S is whichever pipe
+// closes the loop, as long as only one does.
+pub fn infer_start(grid: &Grid<TileType>, start: Position) -> Result<TileType, MazeError> {
+    let connected = connections(grid, start);
+    if let [a, b] = connected[..] {
+        return Ok(TileType::from_openings(a, b).expect("two different directions"));
+    }
+    let closes_loop: Vec<TileType> = TileType::PIPES
         .into_iter()
-        .flatten()
-        .map(|tuple| {
-            std::iter::successors(Some(tuple), |(from_direction, current_position)| {
-                let pipe_type = grid
-                    .get(current_position)
-                    .expect("should not be asking for a grid position that doesn't exist");
-
-                let direction_to_go = match (from_direction, pipe_type) {
-                    (Direction::North, TileType::Vertical) => Direction::South,
-                    (Direction::North, TileType::NorthEast) => Direction::East,
-                    (Direction::North, TileType::NorthWest) => Direction::West,
-                    (Direction::South, TileType::Vertical) => Direction::North,
-                    (Direction::South, TileType::SouthEast) => Direction::East,
-                    (Direction::South, TileType::SouthWest) => Direction::West,
-                    (Direction::East, TileType::Horizontal) => Direction::West,
-                    (Direction::East, TileType::NorthEast) => Direction::North,
-                    (Direction::East, TileType::SouthEast) => Direction::South,
-                    (Direction::West, TileType::Horizontal) => Direction::East,
-                    (Direction::West, TileType::NorthWest) => Direction::North,
-                    (Direction::West, TileType::SouthWest) => Direction::South,
-                    value => {
-                        panic!("uncaught case: {:?}", value);
-                    }
-                };
-                Some(match direction_to_go {
-                    Direction::North => (
-                        Direction::South,
-                        (current_position.0, current_position.1 - 1),
-                    ),
-                    Direction::South => (
-                        Direction::North,
-                        (current_position.0, current_position.1 + 1),
-                    ),
-                    Direction::East => (
-                        Direction::West,
-                        (current_position.0 + 1, current_position.1),
-                    ),
-                    Direction::West => (
-                        Direction::East,
-                        (current_position.0 - 1, current_position.1),
-                    ),
-                })
-            })
-        });
+        .filter(|&pipe| trace(grid, start, pipe).is_ok())
+        .collect();
+    match closes_loop[..] {
+        [pipe] => Ok(pipe),
+        _ => Err(MazeError::AmbiguousStart {
+            start,
+            connections: connected,
+            closes_loop,
+        }),
+    }
+}
 
-    // There should always be 2 paths as per the AOC problem
-    if iters.clone().count() != 2 {
-        panic!("should have exactly 2 paths");
+// Follows the pipes out of S as if it were `start_type` until they lead back
+// into it, failing at the first pipe end with nothing to connect to
+pub fn trace(
+    grid: &Grid<TileType>,
+    start: Position,
+    start_type: TileType,
+) -> Result<Vec<Position>, MazeError> {
+    let mut path = vec![start];
+    let mut position = start;
+    let mut tile = start_type;
+    let mut direction = start_type.openings()[0];
+    loop {
+        let (dx, dy) = direction.offset();
+        let dangling = MazeError::DanglingEnd {
+            position,
+            tile,
+            direction,
+        };
+        let next = grid.offset(position, dx, dy).ok_or(dangling.clone())?;
+        tile = if next == start {
+            start_type
+        } else {
+            grid[next]
+        };
+        if !tile.openings().contains(&direction.opposite()) {
+            return Err(dangling);
+        }
+        if next == start {
+            return Ok(path);
+        }
+        position = next;
+        path.push(position);
+        // Leave through the opening we did not come in from
+        direction = *tile
+            .openings()
+            .iter()
+            .find(|&&opening| opening != direction.opposite())
+            .expect("pipes have two openings");
     }
+}
 
-    let path_a = iters.next().expect("path a should_exist");
-    let path_b = iters.next().expect("path b should exist");
-    let final_position = path_a
-        // Combines to iterators into a single iterator of pairs
-        .zip(path_b)
-        .position(|(a, b)| a.1 == b.1)
-        .expect("should meet in the middle");
+// Every tile of the loop in order starting with S, along with the pipe S
+// stands for
+pub fn find_loop(grid: &Grid<TileType>) -> Result<(Vec<Position>, TileType), MazeError> {
+    let start = find_start(grid)?;
+    let start_type = infer_start(grid, start)?;
+    let path = trace(grid, start, start_type)?;
+    Ok((path, start_type))
+}
 
-    // + 1 for the initial starting position
-    final_position + 1
+pub fn part_one(input: &str) -> Result<usize, MazeError> {
+    let grid = process_input(input)?;
+    let (path, _) = find_loop(&grid)?;
+    // The farthest tile is halfway around the loop
+    Ok(path.len() / 2)
 }
 
 #[cfg(test)]
@@ -164,12 +245,71 @@ mod tests {
 .|.|.
 .L-J.
 .....";
-        assert_eq!(4, part_one(input_one));
+        assert_eq!(Ok(4), part_one(input_one));
         let input_two = "..F7.
 .FJ|.
 SJ.L7
 |F--J
 LJ...";
-        assert_eq!(8, part_one(input_two));
+        assert_eq!(Ok(8), part_one(input_two));
+    }
+
+    #[test]
+    fn test_infer_start() {
+        let grid = process_input("..F7.\n.FJ|.\nSJ.L7\n|F--J\nLJ...").unwrap();
+        assert_eq!(infer_start(&grid, (0, 2)), Ok(TileType::SouthEast));
+        let grid = process_input("-L|F7\n7S-7|\nL|7||\n-L-J|\nL|-JF").unwrap();
+        assert_eq!(infer_start(&grid, (1, 1)), Ok(TileType::SouthEast));
+        // Three neighbors open towards S but only the north and east ones
+        // lead around the loop
+        let grid = process_input(".F-7\n.|.|\n-S-J").unwrap();
+        assert_eq!(infer_start(&grid, (1, 2)), Ok(TileType::NorthEast));
+    }
+
+    #[test]
+    fn test_find_loop_errors() {
+        let grid = process_input(".F7\nFSJ\nLJ.").unwrap();
+        assert_eq!(
+            find_loop(&grid),
+            Err(MazeError::AmbiguousStart {
+                start: (1, 1),
+                connections: Direction::ALL.to_vec(),
+                closes_loop: vec![TileType::NorthEast, TileType::SouthWest],
+            })
+        );
+        let grid = process_input(".S-7.\n.|.|.\n.L-..").unwrap();
+        let err = find_loop(&grid).unwrap_err();
+        assert_eq!(
+            err,
+            MazeError::DanglingEnd {
+                position: (2, 2),
+                tile: TileType::Horizontal,
+                direction: Direction::East,
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "line 3, column 3: - opens east but nothing connects back"
+        );
+        let grid = process_input("S.\n.S").unwrap();
+        assert_eq!(
+            find_loop(&grid),
+            Err(MazeError::MultipleStarts(vec![(0, 0), (1, 1)]))
+        );
+        let grid = process_input("F7\nLJ").unwrap();
+        assert_eq!(find_loop(&grid), Err(MazeError::NoStart));
+    }
+
+    #[test]
+    fn test_process_input_error() {
+        assert_eq!(
+            process_input(".....\n.S-7.\n.|x|.").err(),
+            Some(ParseError::UnexpectedToken {
+                line: 3,
+                column: 3,
+                token: String::from("x"),
+                expected: "a pipe, ground or start tile",
+            })
+        );
     }
 }
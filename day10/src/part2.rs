@@ -1,4 +1,9 @@
-use std::collections::{HashMap, HashSet};
+use crate::{
+    part1::{find_loop, process_input, TileType},
+    MazeError,
+};
+use grid::{Grid, Position};
+use std::collections::HashSet;
 
 #[derive(Debug, Eq, PartialEq)]
 pub enum Status {
@@ -6,197 +11,98 @@ pub enum Status {
     Out,
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
-pub enum Direction {
-    North,
-    East,
-    South,
-    West,
+// How the tiles enclosed by the loop are counted
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Method {
+    // Walk every row flipping between in and out when crossing the loop
+    Scan,
+    // Area of the loop with the shoelace formula turned into a tile count
+    // with Pick's theorem
+    Shoelace,
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
-pub enum TileType {
-    Vertical,
-    Horizontal,
-    NorthEast,
-    NorthWest,
-    SouthWest,
-    SouthEast,
-    Ground,
-    Start,
+// What a tile is with respect to the main loop
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Region {
+    Loop,
+    Inside,
+    Outside,
 }
 
-pub fn process_input(lines: Vec<&str>) -> HashMap<(i64, i64), TileType> {
-    let mut map: HashMap<(i64, i64), TileType> = HashMap::new();
-    lines.iter().enumerate().for_each(|(row, line)| {
-        line.chars().enumerate().for_each(|(column, c)| {
-            let tile = match c {
-                '|' => TileType::Vertical,
-                '-' => TileType::Horizontal,
-                'L' => TileType::NorthEast,
-                'J' => TileType::NorthWest,
-                '7' => TileType::SouthWest,
-                'F' => TileType::SouthEast,
-                '.' => TileType::Ground,
-                'S' => TileType::Start,
-                _ => panic!("Invalid character in input"),
-            };
-            map.insert((column as i64, row as i64), tile);
-        });
-    });
-    map
-}
-
-pub fn part_two(input: &str) -> usize {
-    let mut lines = input.split("\n").collect::<Vec<&str>>();
-    if lines[lines.len() - 1] == "" {
-        lines.pop();
-    }
-    let grid = process_input(lines);
-    let start_position = grid
-        .iter()
-        .find_map(|(key, value)| (value == &TileType::Start).then_some(key))
-        .expect("start exists");
-
-    // Up
-    let north: (i64, i64) = (start_position.0, start_position.1 - 1);
-    let north_position = grid
-        .get(&north)
-        .is_some_and(|pipe_type| match pipe_type {
-            TileType::Vertical | TileType::SouthWest | TileType::SouthEast => true,
-            _ => false,
-        })
-        .then_some((Direction::South, north));
-    // Right to go east
-    let east = (start_position.0 + 1, start_position.1);
-    let east_position = grid
-        .get(&east)
-        .is_some_and(|pipe_type| match pipe_type {
-            TileType::Horizontal | TileType::NorthWest | TileType::SouthWest => true,
-            _ => false,
-        })
-        .then_some((Direction::West, east));
-    // Down to go south
-    let south = (start_position.0, start_position.1 + 1);
-    let south_position = grid
-        .get(&south)
-        .is_some_and(|pipe_type| match pipe_type {
-            TileType::Vertical | TileType::NorthWest | TileType::NorthEast => true,
-            _ => false,
-        })
-        .then_some((Direction::North, south));
-    // Left to go west
-    let west = (start_position.0 - 1, start_position.1);
-    let west_position = grid
-        .get(&west)
-        .is_some_and(|pipe_type| match pipe_type {
-            TileType::Horizontal | TileType::NorthEast | TileType::SouthEast => true,
-            _ => false,
-        })
-        .then_some((Direction::East, west));
-    let mut iters = vec![north_position, south_position, east_position, west_position]
-        .into_iter()
-        .flatten()
-        .map(|tuple| {
-            std::iter::successors(Some(tuple), |(from_direction, current_position)| {
-                let pipe_type = grid
-                    .get(current_position)
-                    .expect("should not be asking for a grid position that doesn't exist");
-
-                let direction_to_go = match (from_direction, pipe_type) {
-                    (Direction::North, TileType::Vertical) => Direction::South,
-                    (Direction::North, TileType::NorthEast) => Direction::East,
-                    (Direction::North, TileType::NorthWest) => Direction::West,
-                    (Direction::South, TileType::Vertical) => Direction::North,
-                    (Direction::South, TileType::SouthEast) => Direction::East,
-                    (Direction::South, TileType::SouthWest) => Direction::West,
-                    (Direction::East, TileType::Horizontal) => Direction::West,
-                    (Direction::East, TileType::NorthEast) => Direction::North,
-                    (Direction::East, TileType::SouthEast) => Direction::South,
-                    (Direction::West, TileType::Horizontal) => Direction::East,
-                    (Direction::West, TileType::NorthWest) => Direction::North,
-                    (Direction::West, TileType::SouthWest) => Direction::South,
-                    value => {
-                        panic!("uncaught case: {:?}", value);
-                    }
-                };
-                Some(match direction_to_go {
-                    Direction::North => (
-                        Direction::South,
-                        (current_position.0, current_position.1 - 1),
-                    ),
-                    Direction::South => (
-                        Direction::North,
-                        (current_position.0, current_position.1 + 1),
-                    ),
-                    Direction::East => (
-                        Direction::West,
-                        (current_position.0 + 1, current_position.1),
-                    ),
-                    Direction::West => (
-                        Direction::East,
-                        (current_position.0 - 1, current_position.1),
-                    ),
-                })
-            })
-        });
-
-    // There should always be 2 paths as per the AOC problem
-    if iters.clone().count() != 2 {
-        panic!("should have exactly 2 paths");
-    }
-
-    let path_a = iters.next().expect("path a should_exist");
-    let path_b = iters.next().expect("path b should exist");
-    let mut zip_it = path_a.zip(path_b);
-    let mut pipe_locations: HashSet<(i64,i64)> = HashSet::from([*start_position]);
-    while let Some((path_a_node, path_b_node)) = zip_it.next() {
-        pipe_locations.insert(path_a_node.1);
-        pipe_locations.insert(path_b_node.1);
-
-        if path_a_node.1 == path_b_node.1 {
-            break;
-        }
-    }
-
-    let result = input
-        .lines()
+// Goes along each row and flips between in and out every time the loop is
+// crossed. Only pipes opening north (|, L and J) count as a crossing so a
+// run like F--J flips once while F--7 does not flip at all.
+pub fn classify(grid: &Grid<TileType>, path: &[Position], start_type: TileType) -> Grid<Region> {
+    let pipe_locations: HashSet<Position> = path.iter().copied().collect();
+    let rows = grid
+        .rows()
         .enumerate()
-        .map(|(y, line)| {
+        .map(|(y, row)| {
             let mut status = Status::Out;
 
-            line.chars()
+            row.iter()
                 .enumerate()
-                .filter(|(x, _)| {
-                    let position = (*x as i64, y as i64);
-                    let pipe_type = grid.get(&position).expect("should be a valid tile");
-                    if pipe_locations.contains(&position) {
-                        if [
-                            TileType::Start,
-                            TileType::Vertical,
-                            TileType::SouthWest,
-                            TileType::SouthEast,
-                        ]
-                        .contains(pipe_type)
+                .map(|(x, &pipe_type)| {
+                    if pipe_locations.contains(&(x, y)) {
+                        let pipe_type = if pipe_type == TileType::Start {
+                            start_type
+                        } else {
+                            pipe_type
+                        };
+                        if [TileType::Vertical, TileType::NorthEast, TileType::NorthWest]
+                            .contains(&pipe_type)
                         {
                             status = match status {
                                 Status::In => Status::Out,
                                 Status::Out => Status::In,
                             };
                         };
-                        false
+                        Region::Loop
                     } else {
                         match status {
-                            Status::In => true,
-                            Status::Out => false,
+                            Status::In => Region::Inside,
+                            Status::Out => Region::Outside,
                         }
                     }
                 })
-                .count()
+                .collect()
         })
-        .sum::<usize>();
+        .collect();
+    Grid::from_rows(rows).expect("rows are as wide as the grid")
+}
+
+fn count_by_scan(grid: &Grid<TileType>, path: &[Position], start_type: TileType) -> usize {
+    classify(grid, path, start_type)
+        .iter()
+        .filter(|&(_, &region)| region == Region::Inside)
+        .count()
+}
 
-    result
+// The loop is a polygon through the centers of its tiles. The shoelace
+// formula gives its area A and Pick's theorem A = i + b / 2 - 1 gives the
+// number of tiles i strictly inside, every loop tile is one of the b
+// boundary points.
+fn count_by_shoelace(path: &[Position]) -> usize {
+    let twice_area = path
+        .iter()
+        .zip(path.iter().cycle().skip(1))
+        .map(|(&(x1, y1), &(x2, y2))| x1 as i64 * y2 as i64 - x2 as i64 * y1 as i64)
+        .sum::<i64>()
+        .unsigned_abs() as usize;
+    (twice_area + 2 - path.len()) / 2
+}
+
+pub fn count_enclosed(grid: &Grid<TileType>, method: Method) -> Result<usize, MazeError> {
+    let (path, start_type) = find_loop(grid)?;
+    Ok(match method {
+        Method::Scan => count_by_scan(grid, &path, start_type),
+        Method::Shoelace => count_by_shoelace(&path),
+    })
+}
+
+pub fn part_two(input: &str) -> Result<usize, MazeError> {
+    let grid = process_input(input)?;
+    count_enclosed(&grid, Method::Shoelace)
 }
 
 #[cfg(test)]
@@ -214,7 +120,7 @@ mod tests {
 .|..|.|..|.
 .L--J.L--J.
 ...........";
-        assert_eq!(4, part_two(input_one));
+        assert_eq!(Ok(4), part_two(input_one));
         let input_two = ".F----7F7F7F7F-7....
 .|F--7||||||||FJ....
 .||.FJ||||||||L7....
@@ -225,8 +131,8 @@ L--J.L7...LJS7F-7L7.
 .....|FJLJ|FJ|F7|.LJ
 ....FJL-7.||.||||...
 ....L---J.LJ.LJLJ...";
-        assert_eq!(8, part_two(input_two));
-   let input_three = "FF7FSF7F7F7F7F7F---7
+        assert_eq!(Ok(8), part_two(input_two));
+        let input_three = "FF7FSF7F7F7F7F7F---7
 L|LJ||||||||||||F--J
 FL-7LJLJ||||||LJL-77
 F--JF--7||LJLJ7F7FJ-
@@ -236,6 +142,21 @@ L---JF-JLJ.||-FJLJJ7
 7-L-JL7||F7|L7F-7F7|
 L.L7LFJ|||||FJL7||LJ
 L7JLJL-JLJLJL--JLJ.L";
-        assert_eq!(10, part_two(input_three));
+        assert_eq!(Ok(10), part_two(input_three));
+
+        // S is a J here so treating it as a | would flip the scan wrongly
+        let input_four = ".F-7.
+.|.|.
+.L-S.
+.....";
+        assert_eq!(Ok(1), part_two(input_four));
+
+        for input in [input_one, input_two, input_three, input_four] {
+            let grid = process_input(input).unwrap();
+            assert_eq!(
+                count_enclosed(&grid, Method::Scan),
+                count_enclosed(&grid, Method::Shoelace)
+            );
+        }
     }
 }
@@ -0,0 +1,224 @@
+use crate::{
+    part1::{find_loop, Direction, TileType},
+    part2::{classify, Region},
+    MazeError,
+};
+use grid::{Grid, Position};
+use std::fmt::Write;
+
+// RGB colors shared by the image formats
+const LOOP: (u8, u8, u8) = (214, 39, 40);
+const LOOP_BACKGROUND: (u8, u8, u8) = (255, 255, 255);
+const INSIDE: (u8, u8, u8) = (44, 160, 44);
+const OUTSIDE: (u8, u8, u8) = (230, 230, 230);
+
+// Box drawing character for a pipe, ground and an unknown start are left as
+// they are in the input
+pub fn box_char(tile: TileType) -> char {
+    match tile {
+        TileType::Vertical => '│',
+        TileType::Horizontal => '─',
+        TileType::NorthEast => '└',
+        TileType::NorthWest => '┘',
+        TileType::SouthWest => '┐',
+        TileType::SouthEast => '┌',
+        TileType::Ground => '.',
+        TileType::Start => 'S',
+    }
+}
+
+// The maze with S replaced by the pipe it stands for and every tile marked
+// as part of the loop, inside or outside of it
+#[derive(Debug)]
+pub struct Picture {
+    tiles: Grid<TileType>,
+    regions: Grid<Region>,
+    path: Vec<Position>,
+}
+
+impl Picture {
+    pub fn new(grid: &Grid<TileType>) -> Result<Self, MazeError> {
+        let (path, start_type) = find_loop(grid)?;
+        let regions = classify(grid, &path, start_type);
+        let tiles = grid.map(|&tile| match tile {
+            TileType::Start => start_type,
+            tile => tile,
+        });
+        Ok(Picture {
+            tiles,
+            regions,
+            path,
+        })
+    }
+
+    // Only the loop is drawn as pipes, every other tile is shaded dark when
+    // it is inside of the loop and light when it is outside
+    pub fn to_text(&self) -> String {
+        let cells = Grid::from_rows(
+            self.tiles
+                .rows()
+                .zip(self.regions.rows())
+                .map(|(tiles, regions)| {
+                    tiles.iter().copied().zip(regions.iter().copied()).collect()
+                })
+                .collect(),
+        )
+        .expect("tiles and regions are the same size");
+        cells.render(|&(tile, region)| match region {
+            Region::Loop => box_char(tile),
+            Region::Inside => '▓',
+            Region::Outside => '░',
+        })
+    }
+
+    // Squares of `cell` pixels shaded like the text view with the loop
+    // drawn as a single polygon through the centers of its tiles
+    pub fn to_svg(&self, cell: usize) -> String {
+        let cell = cell.max(1);
+        let (width, height) = (self.tiles.width() * cell, self.tiles.height() * cell);
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n",
+            width, height, width, height
+        );
+        writeln!(
+            svg,
+            "  <rect width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+            width,
+            height,
+            hex(OUTSIDE)
+        )
+        .unwrap();
+        for ((x, y), _) in self
+            .regions
+            .iter()
+            .filter(|&(_, &region)| region == Region::Inside)
+        {
+            writeln!(
+                svg,
+                "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                x * cell,
+                y * cell,
+                cell,
+                cell,
+                hex(INSIDE)
+            )
+            .unwrap();
+        }
+        let points: Vec<String> = self
+            .path
+            .iter()
+            .map(|&(x, y)| format!("{},{}", x * cell + cell / 2, y * cell + cell / 2))
+            .collect();
+        writeln!(
+            svg,
+            "  <polygon points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\"/>",
+            points.join(" "),
+            hex(LOOP),
+            (cell / 3).max(1)
+        )
+        .unwrap();
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    // Binary PPM (P6) with `scale` pixels per tile. Loop tiles draw their
+    // pipe as a band a third of the tile wide, scales of 3 and up look best.
+    pub fn to_ppm(&self, scale: usize) -> Vec<u8> {
+        let scale = scale.max(1);
+        let (width, height) = (self.tiles.width() * scale, self.tiles.height() * scale);
+        let mut ppm = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+        ppm.reserve(width * height * 3);
+
+        let center = scale / 2;
+        let band = scale / 6;
+        for py in 0..height {
+            for px in 0..width {
+                let position = (px / scale, py / scale);
+                let (dx, dy) = (px % scale, py % scale);
+                let (r, g, b) = match self.regions[position] {
+                    Region::Inside => INSIDE,
+                    Region::Outside => OUTSIDE,
+                    Region::Loop => {
+                        let openings = self.tiles[position].openings();
+                        let opens = |direction| openings.contains(&direction);
+                        let vertical = dx.abs_diff(center) <= band
+                            && ((dy <= center && opens(Direction::North))
+                                || (dy >= center && opens(Direction::South)));
+                        let horizontal = dy.abs_diff(center) <= band
+                            && ((dx <= center && opens(Direction::West))
+                                || (dx >= center && opens(Direction::East)));
+                        if vertical || horizontal {
+                            LOOP
+                        } else {
+                            LOOP_BACKGROUND
+                        }
+                    }
+                };
+                ppm.extend([r, g, b]);
+            }
+        }
+        ppm
+    }
+}
+
+fn hex((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part1::process_input;
+
+    const INPUT: &str = ".F-7.
+.|.|.
+.L-S.
+.....";
+
+    #[test]
+    fn test_to_text() {
+        let picture = Picture::new(&process_input(INPUT).unwrap()).unwrap();
+        assert_eq!(
+            picture.to_text(),
+            "░┌─┐░
+░│▓│░
+░└─┘░
+░░░░░
+"
+        );
+    }
+
+    #[test]
+    fn test_to_svg() {
+        let picture = Picture::new(&process_input(INPUT).unwrap()).unwrap();
+        let svg = picture.to_svg(10);
+        assert!(
+            svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"50\" height=\"40\"")
+        );
+        assert_eq!(svg.matches("#2ca02c").count(), 1);
+        assert!(svg.contains("points=\"35,25 35,15 35,5 25,5 15,5 15,15 15,25 25,25\""));
+        // Scales below one pixel per tile are drawn at one like the PPM
+        assert_eq!(picture.to_svg(0), picture.to_svg(1));
+    }
+
+    #[test]
+    fn test_to_ppm() {
+        let picture = Picture::new(&process_input(INPUT).unwrap()).unwrap();
+        let ppm = picture.to_ppm(3);
+        let header = b"P6\n15 12\n255\n";
+        assert_eq!(&ppm[..header.len()], header);
+        let pixels = &ppm[header.len()..];
+        assert_eq!(pixels.len(), 15 * 12 * 3);
+        let pixel = |x: usize, y: usize| {
+            let i = (y * 15 + x) * 3;
+            (pixels[i], pixels[i + 1], pixels[i + 2])
+        };
+        assert_eq!(pixel(0, 0), OUTSIDE);
+        assert_eq!(pixel(7, 4), INSIDE);
+        // Center and east arm of the F in the top left of the loop
+        assert_eq!(pixel(4, 1), LOOP);
+        assert_eq!(pixel(5, 1), LOOP);
+        assert_eq!(pixel(3, 1), LOOP_BACKGROUND);
+        assert_eq!(pixel(4, 0), LOOP_BACKGROUND);
+    }
+}
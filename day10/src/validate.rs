@@ -0,0 +1,156 @@
+use crate::{
+    part1::{find_start, infer_start, Direction, TileType},
+    MazeError,
+};
+use grid::{Grid, Position};
+use std::collections::HashSet;
+
+// The neighbor `direction` leads to when both tiles connect to each other.
+// A start whose pipe is not known connects to anything that opens towards it.
+fn linked(tiles: &Grid<TileType>, position: Position, direction: Direction) -> Option<Position> {
+    let opens = |tile: TileType, direction: Direction| {
+        tile == TileType::Start || tile.openings().contains(&direction)
+    };
+    let (dx, dy) = direction.offset();
+    let neighbor = tiles.offset(position, dx, dy)?;
+    (opens(tiles[position], direction) && opens(tiles[neighbor], direction.opposite()))
+        .then_some(neighbor)
+}
+
+// Every problem with the maze rather than just the first one: a missing,
+// repeated or ambiguous start, then every pipe end with nothing to connect to
+// and every group of connected pipes that does not reach the start, both in
+// reading order
+pub fn validate(grid: &Grid<TileType>) -> Vec<MazeError> {
+    let mut problems = Vec::new();
+    let start = find_start(grid).map_err(|err| problems.push(err)).ok();
+    let start_type = start.and_then(|start| {
+        infer_start(grid, start)
+            .map_err(|err| problems.push(err))
+            .ok()
+    });
+    let tiles = grid.map(|&tile| match (tile, start_type) {
+        (TileType::Start, Some(start_type)) => start_type,
+        (tile, _) => tile,
+    });
+
+    for (position, &tile) in tiles.iter() {
+        for &direction in tile.openings() {
+            if linked(&tiles, position, direction).is_none() {
+                problems.push(MazeError::DanglingEnd {
+                    position,
+                    tile,
+                    direction,
+                });
+            }
+        }
+    }
+
+    let mut seen = HashSet::new();
+    for (position, &tile) in tiles.iter() {
+        if tile == TileType::Ground || seen.contains(&position) {
+            continue;
+        }
+        seen.insert(position);
+        let mut segment = vec![position];
+        let mut index = 0;
+        while let Some(&current) = segment.get(index) {
+            for direction in Direction::ALL {
+                if let Some(neighbor) = linked(&tiles, current, direction) {
+                    if seen.insert(neighbor) {
+                        segment.push(neighbor);
+                    }
+                }
+            }
+            index += 1;
+        }
+        if start.is_none_or(|start| !segment.contains(&start)) {
+            segment.sort_by_key(|&(x, y)| (y, x));
+            problems.push(MazeError::Disconnected(segment));
+        }
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part1::{find_loop, process_input};
+
+    #[test]
+    fn test_validate() {
+        let grid = process_input(
+            "-L|F7
+7S-7|
+L|7||
+-L-J|
+L|-JF",
+        )
+        .unwrap();
+        let problems = validate(&grid);
+        // The loop itself is fine, the junk around it is not
+        let (path, _) = find_loop(&grid).unwrap();
+        assert!(problems.iter().all(|problem| match problem {
+            MazeError::DanglingEnd { position, .. } => !path.contains(position),
+            _ => true,
+        }));
+        assert!(problems.contains(&MazeError::DanglingEnd {
+            position: (0, 0),
+            tile: TileType::Horizontal,
+            direction: Direction::West,
+        }));
+        assert!(problems.contains(&MazeError::Disconnected(vec![
+            (3, 0),
+            (4, 0),
+            (4, 1),
+            (4, 2),
+            (4, 3)
+        ])));
+
+        let grid = process_input(".S-7.\n.|.|.\n.L-..\n.F7..").unwrap();
+        assert_eq!(
+            validate(&grid),
+            vec![
+                MazeError::DanglingEnd {
+                    position: (3, 1),
+                    tile: TileType::Vertical,
+                    direction: Direction::South,
+                },
+                MazeError::DanglingEnd {
+                    position: (2, 2),
+                    tile: TileType::Horizontal,
+                    direction: Direction::East,
+                },
+                MazeError::DanglingEnd {
+                    position: (1, 3),
+                    tile: TileType::SouthEast,
+                    direction: Direction::South,
+                },
+                MazeError::DanglingEnd {
+                    position: (2, 3),
+                    tile: TileType::SouthWest,
+                    direction: Direction::South,
+                },
+                MazeError::Disconnected(vec![(1, 3), (2, 3)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_start() {
+        let grid = process_input(".F7\nFSJ\nLJ.").unwrap();
+        assert_eq!(
+            validate(&grid)[0].to_string(),
+            "line 2, column 2: the start connects to 4 neighbors (north, east, south, west) \
+             and the loop closes with any of L 7 in its place"
+        );
+        let grid = process_input("F7\nLJ").unwrap();
+        assert_eq!(
+            validate(&grid),
+            vec![
+                MazeError::NoStart,
+                MazeError::Disconnected(vec![(0, 0), (1, 0), (0, 1), (1, 1)])
+            ]
+        );
+    }
+}
@@ -1,101 +1,128 @@
-use std::collections::HashMap;
+use common::parse::ParseError;
+use grid::{Grid, Position};
+use std::num::NonZeroU64;
 
 #[derive(Debug)]
-pub struct Grid {
-    graph: HashMap<(i64, i64), i64>,
-    nodes: Vec<Vec<Node>>,
+pub struct Universe {
+    nodes: Grid<Node>,
+    // Number of empty rows above each row and empty columns left of each
+    // column, so the expansion between two galaxies is a subtraction
+    empty_rows_before: Vec<usize>,
+    empty_cols_before: Vec<usize>,
 }
 
-impl Grid {
-    fn print_universe(&self) -> Vec<String> {
+impl Universe {
+    pub fn print_universe(&self) -> Vec<String> {
         self.nodes
-            .iter()
-            .map(|l| {
-                let mut s = String::from("");
-                l.iter().for_each(|c| {
-                    s.push(c.symbol);
-                });
-                s
-            })
+            .render(|node| node.symbol)
+            .lines()
+            .map(String::from)
             .collect()
     }
-    // vec of empty row
-    fn find_empty_rows(&self) -> Vec<usize> {
-        let mut empty_rows: Vec<usize> = Vec::new();
-        self.nodes.iter().enumerate().for_each(|(index, row)| {
-            if row.iter().filter(|x| x.symbol == '#').count() == 0 {
-                empty_rows.push(index);
-            }
-        });
-        empty_rows
-    }
 
-    // vec of empty cols
-    fn find_empty_cols(&self) -> Vec<usize> {
-        // Represents the count of items that are not . in a given col
-        let mut col_items: Vec<usize> = vec![0; self.nodes[0].len()];
-        self.nodes.iter().for_each(|row| {
-            row.iter().enumerate().for_each(|(y, c)| match c.symbol {
-                '#' => col_items[y] += 1,
-                _ => {}
-            });
-        });
-
-        col_items
-            .iter()
-            .enumerate()
-            .filter_map(|(index, &v)| {
-                if v == 0 {
-                    return Some(index);
-                }
-                None
-            })
-            .collect()
+    // Whether each row and each column has no galaxies in it, found in a
+    // single pass over the universe
+    fn empty_lines(&self) -> (Vec<bool>, Vec<bool>) {
+        let mut empty_rows = vec![true; self.nodes.height()];
+        let mut empty_cols = vec![true; self.nodes.width()];
+        for ((x, y), _) in self.nodes.iter().filter(|(_, node)| node.symbol == '#') {
+            empty_rows[y] = false;
+            empty_cols[x] = false;
+        }
+        (empty_rows, empty_cols)
     }
 
-    fn expand_universe(&self) -> Grid {
-        let mut expanded_universe = self.nodes.clone();
-        let mut offset: usize = 0;
+    // Inserts a copy of every empty row and column, only practical for an
+    // expansion factor of 2 but handy for checking `shortest_path`
+    pub fn expand_universe(&self) -> Universe {
+        let (empty_rows, empty_cols) = self.empty_lines();
+        let mut expanded_universe: Vec<Vec<Node>> =
+            self.nodes.rows().map(|row| row.to_vec()).collect();
 
         // Expand rows
-        for &row_index in &self.find_empty_rows() {
-            let empty_row = vec![Node::new(); self.nodes[0].len()]; // Create an empty row with the same length as other rows
+        for (offset, &row_index) in indices(&empty_rows).iter().enumerate() {
+            let empty_row = vec![Node::new(); self.nodes.width()]; // Create an empty row with the same length as other rows
             expanded_universe.insert(row_index + offset, empty_row);
-            offset += 1;
         }
 
         // Expand columns
-        for &col_index in self.find_empty_cols().iter().rev() {
+        for &col_index in indices(&empty_cols).iter().rev() {
             for row in expanded_universe.iter_mut() {
                 let node = Node::new();
                 row.insert(col_index, node); // Insert a duplicate node in each row
             }
         }
-        Grid {
-            nodes: expanded_universe,
-            graph: self.graph.clone(),
-        }
+        Universe::new(Grid::from_rows(expanded_universe).expect("every row was expanded the same"))
     }
 
-    // Method to find all galaxy pairs
-    fn galaxy_pairs(&self) -> Vec<(Node, Node)> {
-        // Generate all possible pairs
-        todo!()
+    fn new(nodes: Grid<Node>) -> Self {
+        let mut universe = Universe {
+            nodes,
+            empty_rows_before: Vec::new(),
+            empty_cols_before: Vec::new(),
+        };
+        let (empty_rows, empty_cols) = universe.empty_lines();
+        universe.empty_rows_before = prefix_counts(&empty_rows);
+        universe.empty_cols_before = prefix_counts(&empty_cols);
+        universe
     }
 
-    // Method to calculate the shortest path between two galaxies
-    fn shortest_path(&self, start: Node, end: Node) -> usize {
-        // Calculate shortest path length
-        todo!()
+    pub fn galaxies(&self) -> Vec<Position> {
+        self.nodes
+            .iter()
+            .filter(|(_, node)| node.symbol == '#')
+            .map(|(position, _)| position)
+            .collect()
     }
 
-    // Method to sum the path lengths for all galaxy pairs
-    fn sum_of_paths(&self) -> usize {
-        // Iterate over galaxy pairs and sum their path lengths
-        todo!()
+    // Every unordered pair of different galaxies
+    pub fn galaxy_pairs(&self) -> Vec<(Position, Position)> {
+        let galaxies = self.galaxies();
+        galaxies
+            .iter()
+            .enumerate()
+            .flat_map(|(index, &start)| galaxies[index + 1..].iter().map(move |&end| (start, end)))
+            .collect()
+    }
+
+    // Steps between two galaxies once every empty row and column has grown
+    // to `factor` rows or columns, an empty row can not shrink to nothing
+    pub fn shortest_path(&self, start: Position, end: Position, factor: NonZeroU64) -> u64 {
+        let distance = |a: usize, b: usize, empty_before: &[usize]| {
+            let (low, high) = (a.min(b), a.max(b));
+            let empty = (empty_before[high] - empty_before[low]) as u64;
+            (high - low) as u64 + empty * (factor.get() - 1)
+        };
+        distance(start.0, end.0, &self.empty_cols_before)
+            + distance(start.1, end.1, &self.empty_rows_before)
+    }
+
+    pub fn sum_of_paths(&self, factor: NonZeroU64) -> u64 {
+        self.galaxy_pairs()
+            .into_iter()
+            .map(|(start, end)| self.shortest_path(start, end, factor))
+            .sum()
     }
 }
 
+fn indices(empty: &[bool]) -> Vec<usize> {
+    empty
+        .iter()
+        .enumerate()
+        .filter_map(|(index, &empty)| empty.then_some(index))
+        .collect()
+}
+
+// counts[i] is how many of the first i rows or columns are empty
+fn prefix_counts(empty: &[bool]) -> Vec<usize> {
+    let mut counts = Vec::with_capacity(empty.len() + 1);
+    counts.push(0);
+    for &empty in empty {
+        counts.push(counts[counts.len() - 1] + usize::from(empty));
+    }
+    counts
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct Node {
     id: Option<i64>,
@@ -111,56 +138,29 @@ impl Node {
     }
 }
 
-pub fn process(lines: Vec<&str>) -> Grid {
-    let mut nodes = Vec::new();
-    let mut graph = HashMap::new();
-    let mut unique_id = 1;
-    lines.iter().enumerate().for_each(|(y, line)| {
-        let mut row = Vec::new();
-        line.chars().enumerate().for_each(|(x, c)| {
-            if c == '#' {
-                graph.insert((x as i64, y as i64), unique_id);
-                row.push(Node {
-                    id: Some(unique_id),
-                    symbol: c,
-                });
-                unique_id += 1;
-            } else {
-                row.push(Node {
-                    id: None,
-                    symbol: c,
-                });
-            }
-        });
-        nodes.push(row);
-    });
-    Grid { nodes, graph }
+pub fn process(input: &str) -> Result<Universe, ParseError> {
+    let mut unique_id = 0;
+    let nodes = Grid::parse(input, "# or .", |c| match c {
+        '#' => {
+            unique_id += 1;
+            Some(Node {
+                id: Some(unique_id),
+                symbol: c,
+            })
+        }
+        '.' => Some(Node::new()),
+        _ => None,
+    })?;
+    Ok(Universe::new(nodes))
 }
 
-pub fn part_one(input: &str) -> usize {
-    let mut lines = input.split("\n").collect::<Vec<&str>>();
-    if lines[lines.len() - 1] == "" {
-        lines.pop();
-    }
-    let mut grid = process(lines);
-
-    grid = grid.expand_universe();
-
-    grid.nodes.iter().for_each(|l| {
-        let mut s = String::from("");
-        l.iter().for_each(|c| {
-            s.push(c.symbol);
-        });
-        println!("{}", s);
-    });
+// Every empty row and column becomes two
+const EXPANSION: NonZeroU64 = NonZeroU64::new(2).unwrap();
 
-    todo!()
+pub fn part_one(input: &str) -> Result<u64, ParseError> {
+    Ok(process(input)?.sum_of_paths(EXPANSION))
 }
 
-/**
- * galaxies!/2
- */
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -176,7 +176,7 @@ mod tests {
 ..........
 .......#..
 #...#.....";
-        assert_eq!(374, part_one(input_one));
+        assert_eq!(Ok(374), part_one(input_one));
     }
 
     #[test]
@@ -203,13 +203,60 @@ mod tests {
 .............
 .........#...
 #....#.......";
-        let grid = process(input.split("\n").collect::<Vec<&str>>());
+        let universe = process(input).unwrap();
         assert_eq!(
             expected
                 .split("\n")
-                .map(|s| String::from(s))
+                .map(String::from)
                 .collect::<Vec<String>>(),
-            grid.expand_universe().print_universe(),
+            universe.expand_universe().print_universe(),
+        );
+        // Expanding by hand and measuring without expansion agrees with the
+        // prefix sums
+        assert_eq!(
+            universe.expand_universe().sum_of_paths(NonZeroU64::MIN),
+            374
         );
     }
+
+    #[test]
+    fn test_sum_of_paths() {
+        let universe = process(
+            "...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....",
+        )
+        .unwrap();
+        assert_eq!(universe.galaxy_pairs().len(), 36);
+        let galaxies = universe.galaxies();
+        assert_eq!(
+            universe.shortest_path(galaxies[4], galaxies[8], factor(2)),
+            9
+        );
+        assert_eq!(
+            universe.shortest_path(galaxies[8], galaxies[4], factor(2)),
+            9
+        );
+        assert_eq!(universe.sum_of_paths(factor(10)), 1030);
+        assert_eq!(universe.sum_of_paths(factor(100)), 8410);
+    }
+
+    fn factor(factor: u64) -> NonZeroU64 {
+        NonZeroU64::new(factor).unwrap()
+    }
+
+    #[test]
+    fn test_smallest_factor() {
+        // Empty columns stay as they are, an empty one can not be removed
+        let universe = process("#.#").unwrap();
+        assert_eq!(universe.sum_of_paths(NonZeroU64::MIN), 2);
+        assert_eq!(universe.sum_of_paths(factor(2)), 3);
+    }
 }
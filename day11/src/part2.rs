@@ -0,0 +1,30 @@
+use crate::part1::process;
+use common::parse::ParseError;
+use std::num::NonZeroU64;
+
+// Every empty row and column becomes a million
+const EXPANSION: NonZeroU64 = NonZeroU64::new(1_000_000).unwrap();
+
+pub fn part_two(input: &str) -> Result<u64, ParseError> {
+    Ok(process(input)?.sum_of_paths(EXPANSION))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_two() {
+        let input = "...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....";
+        assert_eq!(Ok(82000210), part_two(input));
+    }
+}
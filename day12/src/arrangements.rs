@@ -0,0 +1,217 @@
+use crate::part1::{step, Memo, Record, Spring};
+
+// Every concrete arrangement of a record, damaged springs are tried before
+// operational ones at each unknown so they come out in the same order
+// `Record::nth_arrangement` numbers them. Branches are only followed when
+// the counting table says they lead somewhere, so each arrangement takes
+// work proportional to the length of the record no matter how many dead
+// ends there are.
+pub struct Arrangements<'a> {
+    memo: Memo<'a>,
+    // The arrangement being built, shared by every branch and cut back to
+    // the depth of the branch taken next
+    springs: Vec<Spring>,
+    // Branches still to take as (depth, group, run, spring at depth - 1),
+    // the last one is taken first and only the root has no spring
+    stack: Vec<(usize, usize, usize, Option<Spring>)>,
+}
+
+impl<'a> Arrangements<'a> {
+    fn new(record: &'a Record) -> Self {
+        let mut memo = Memo::new(record);
+        let stack = if memo.count(0, 0, 0) > 0 {
+            vec![(0, 0, 0, None)]
+        } else {
+            Vec::new()
+        };
+        Arrangements {
+            memo,
+            springs: Vec::with_capacity(record.springs().len()),
+            stack,
+        }
+    }
+}
+
+impl Iterator for Arrangements<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        let record = self.memo.record;
+        while let Some((position, group, run, last)) = self.stack.pop() {
+            self.springs.truncate(position.saturating_sub(1));
+            self.springs.extend(last);
+            let Some(&spring) = record.springs().get(position) else {
+                return Some(
+                    self.springs
+                        .iter()
+                        .map(|&spring| char::from(spring))
+                        .collect(),
+                );
+            };
+            // Pushed in reverse so damaged is popped first
+            for choice in [Spring::Operational, Spring::Damaged] {
+                if spring != choice && spring != Spring::Unknown {
+                    continue;
+                }
+                let Some((group, run)) = step(record.groups(), choice, group, run) else {
+                    continue;
+                };
+                if self.memo.count(position + 1, group, run) > 0 {
+                    self.stack.push((position + 1, group, run, Some(choice)));
+                }
+            }
+        }
+        None
+    }
+}
+
+// `count` arrangements spread evenly over all of them, every one of them
+// when there are not that many
+pub struct Sample<'a> {
+    memo: Memo<'a>,
+    total: u128,
+    count: u128,
+    next: u128,
+}
+
+impl Iterator for Sample<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        if self.next >= self.count {
+            return None;
+        }
+        // next * total / count without overflowing
+        let index = self.next * (self.total / self.count)
+            + self.next * (self.total % self.count) / self.count;
+        self.next += 1;
+        unrank(&mut self.memo, index)
+    }
+}
+
+// The arrangement at `index` in the order `Arrangements` yields them, picks
+// damaged at each unknown when there are more than `index` arrangements
+// that way and skips past all of them otherwise
+fn unrank(memo: &mut Memo, mut index: u128) -> Option<String> {
+    let record = memo.record;
+    let (mut group, mut run) = (0, 0);
+    let mut arrangement = String::with_capacity(record.springs().len());
+    for (position, &spring) in record.springs().iter().enumerate() {
+        let mut chosen = None;
+        for choice in [Spring::Damaged, Spring::Operational] {
+            if spring != choice && spring != Spring::Unknown {
+                continue;
+            }
+            let Some((next_group, next_run)) = step(record.groups(), choice, group, run) else {
+                continue;
+            };
+            let count = memo.count(position + 1, next_group, next_run);
+            if index < count {
+                chosen = Some((choice, next_group, next_run));
+                break;
+            }
+            index -= count;
+        }
+        let (choice, next_group, next_run) = chosen?;
+        arrangement.push(char::from(choice));
+        (group, run) = (next_group, next_run);
+    }
+    (index == 0 && memo.count(record.springs().len(), group, run) == 1).then_some(arrangement)
+}
+
+impl Record {
+    // Lazily yields every arrangement, use `take` to stop after a few
+    pub fn iter_arrangements(&self) -> Arrangements<'_> {
+        Arrangements::new(self)
+    }
+
+    pub fn nth_arrangement(&self, index: u128) -> Option<String> {
+        unrank(&mut Memo::new(self), index)
+    }
+
+    pub fn sample_arrangements(&self, count: usize) -> Sample<'_> {
+        let mut memo = Memo::new(self);
+        let total = memo.count(0, 0, 0);
+        Sample {
+            memo,
+            total,
+            count: total.min(count as u128),
+            next: 0,
+        }
+    }
+
+    // The record next to an arrangement of it and just the springs that
+    // arrangement filled in for the unknowns, for lining up many of them:
+    // `???.### | #.#.### | #.#`
+    pub fn diff(&self, arrangement: &str) -> String {
+        let pattern: String = self
+            .springs()
+            .iter()
+            .map(|&spring| char::from(spring))
+            .collect();
+        let filled: String = self
+            .springs()
+            .iter()
+            .zip(arrangement.chars())
+            .map(|(&spring, c)| if spring == Spring::Unknown { c } else { ' ' })
+            .collect();
+        format!("{} | {} | {}", pattern, arrangement, filled.trim_end())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::part1::process_input;
+
+    #[test]
+    fn test_iter_arrangements() {
+        let record = process_input(vec!["?###???????? 3,2,1"]).unwrap().remove(0);
+        let all: Vec<String> = record.iter_arrangements().collect();
+        assert_eq!(all.len() as u128, record.arrangements());
+        assert_eq!(all[0], ".###.##.#...");
+        assert_eq!(all[9], ".###....##.#");
+        for (index, arrangement) in all.iter().enumerate() {
+            assert_eq!(
+                record.nth_arrangement(index as u128).as_ref(),
+                Some(arrangement)
+            );
+        }
+        assert_eq!(record.nth_arrangement(10), None);
+
+        assert_eq!(record.iter_arrangements().take(3).count(), 3);
+        let impossible = process_input(vec!["### 2"]).unwrap().remove(0);
+        assert_eq!(impossible.iter_arrangements().next(), None);
+        assert_eq!(impossible.sample_arrangements(3).next(), None);
+    }
+
+    #[test]
+    fn test_sample_arrangements() {
+        let record = process_input(vec!["?###???????? 3,2,1"]).unwrap().remove(0);
+        let all: Vec<String> = record.iter_arrangements().collect();
+        let sample: Vec<String> = record.sample_arrangements(4).collect();
+        assert_eq!(
+            sample,
+            vec![
+                all[0].clone(),
+                all[2].clone(),
+                all[5].clone(),
+                all[7].clone()
+            ]
+        );
+        assert_eq!(record.sample_arrangements(100).count(), 10);
+
+        // Sampling does not have to walk through everything before it
+        let unfolded = record.unfold(5);
+        assert_eq!(unfolded.sample_arrangements(3).count(), 3);
+        assert!(unfolded
+            .sample_arrangements(3)
+            .all(|arrangement| !arrangement.contains('?')));
+    }
+
+    #[test]
+    fn test_diff() {
+        let record = process_input(vec!["???.### 1,1,3"]).unwrap().remove(0);
+        let arrangement = record.iter_arrangements().next().unwrap();
+        assert_eq!(record.diff(&arrangement), "???.### | #.#.### | #.#");
+    }
+}
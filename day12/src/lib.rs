@@ -1 +1,26 @@
+use common::{Answer, Solution};
+use std::error::Error;
+
+pub mod arrangements;
 pub mod part1;
+pub mod part2;
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    fn day(&self) -> u8 {
+        12
+    }
+
+    fn title(&self) -> &'static str {
+        "Hot Springs"
+    }
+
+    fn part_one(&self, input: &str) -> Result<Answer, Box<dyn Error>> {
+        part1::part_one(input).map(Answer::from).map_err(Into::into)
+    }
+
+    fn part_two(&self, input: &str) -> Option<Result<Answer, Box<dyn Error>>> {
+        Some(part2::part_two(input).map(Answer::from).map_err(Into::into))
+    }
+}
@@ -1,9 +1,197 @@
-pub fn part_one(input: &str) -> usize {
+use common::parse::{Line, ParseError};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Spring {
+    Operational,
+    Damaged,
+    Unknown,
+}
+
+impl From<Spring> for char {
+    fn from(spring: Spring) -> char {
+        match spring {
+            Spring::Operational => '.',
+            Spring::Damaged => '#',
+            Spring::Unknown => '?',
+        }
+    }
+}
+
+impl Spring {
+    fn from_char(c: char) -> Option<Spring> {
+        match c {
+            '.' => Some(Spring::Operational),
+            '#' => Some(Spring::Damaged),
+            '?' => Some(Spring::Unknown),
+            _ => None,
+        }
+    }
+}
+
+// One row of the condition records, the springs and the sizes of the
+// contiguous groups of damaged springs in order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    springs: Vec<Spring>,
+    groups: Vec<usize>,
+}
+
+impl Record {
+    pub fn new(springs: Vec<Spring>, groups: Vec<usize>) -> Self {
+        Record { springs, groups }
+    }
+
+    pub fn springs(&self) -> &[Spring] {
+        &self.springs
+    }
+
+    pub fn groups(&self) -> &[usize] {
+        &self.groups
+    }
+
+    // The record repeated `copies` times with an unknown spring between the
+    // copies of the springs
+    pub fn unfold(&self, copies: usize) -> Record {
+        let mut springs = Vec::with_capacity((self.springs.len() + 1) * copies);
+        for copy in 0..copies {
+            if copy > 0 {
+                springs.push(Spring::Unknown);
+            }
+            springs.extend_from_slice(&self.springs);
+        }
+        Record {
+            springs,
+            groups: self.groups.repeat(copies),
+        }
+    }
+
+    // Number of ways to replace every unknown spring so the damaged ones form
+    // exactly the listed groups
+    pub fn arrangements(&self) -> u128 {
+        Memo::new(self).count(0, 0, 0)
+    }
+}
+
+// Drawn the way it appears in the input
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let springs: String = self
+            .springs
+            .iter()
+            .map(|&spring| char::from(spring))
+            .collect();
+        let groups: Vec<String> = self.groups.iter().map(ToString::to_string).collect();
+        write!(f, "{} {}", springs, groups.join(","))
+    }
+}
+
+// The state after deciding the next spring is `spring`, None when that would
+// make a group too long or end it too early
+pub(crate) fn step(
+    groups: &[usize],
+    spring: Spring,
+    group: usize,
+    run: usize,
+) -> Option<(usize, usize)> {
+    match spring {
+        Spring::Damaged => {
+            (group < groups.len() && run < groups[group]).then_some((group, run + 1))
+        }
+        _ if run == 0 => Some((group, 0)),
+        _ => (run == groups[group]).then_some((group + 1, 0)),
+    }
+}
+
+// Arrangements of the springs from `position` on given `group` groups are
+// already complete and the current run of damaged springs is `run` long.
+// Runs longer than the longest group can never be valid so the table only
+// needs room up to it.
+pub(crate) struct Memo<'a> {
+    pub(crate) record: &'a Record,
+    longest: usize,
+    counts: Vec<Option<u128>>,
+}
+
+impl<'a> Memo<'a> {
+    pub(crate) fn new(record: &'a Record) -> Self {
+        let longest = record.groups.iter().copied().max().unwrap_or(0);
+        Memo {
+            record,
+            longest,
+            counts: vec![
+                None;
+                (record.springs.len() + 1) * (record.groups.len() + 1) * (longest + 1)
+            ],
+        }
+    }
+
+    pub(crate) fn count(&mut self, position: usize, group: usize, run: usize) -> u128 {
+        let groups = &self.record.groups;
+        let Some(&spring) = self.record.springs.get(position) else {
+            // Every group has to be closed off, the last one may still be
+            // running at the end of the row
+            let closed = run == 0 && group == groups.len();
+            let running = group + 1 == groups.len() && run == groups[group];
+            return u128::from(closed || running);
+        };
+
+        let index = (position * (groups.len() + 1) + group) * (self.longest + 1) + run;
+        if let Some(count) = self.counts[index] {
+            return count;
+        }
+
+        let mut count = 0;
+        for choice in [Spring::Damaged, Spring::Operational] {
+            if spring == choice || spring == Spring::Unknown {
+                if let Some((group, run)) = step(groups, choice, group, run) {
+                    count += self.count(position + 1, group, run);
+                }
+            }
+        }
+        self.counts[index] = Some(count);
+        count
+    }
+}
+
+pub fn process_input(lines: Vec<&str>) -> Result<Vec<Record>, ParseError> {
+    lines
+        .iter()
+        .enumerate()
+        .map(|(index, &text)| {
+            let line = Line::new(index, text);
+            let (springs, groups) = line.split_once(text, " ", "group sizes")?;
+            let springs = springs
+                .chars()
+                .enumerate()
+                .map(|(index, c)| {
+                    Spring::from_char(c).ok_or_else(|| line.unexpected_char(index, c, "., # or ?"))
+                })
+                .collect::<Result<Vec<Spring>, ParseError>>()?;
+            let groups = groups
+                .split(',')
+                .map(|size| line.parse(size))
+                .collect::<Result<Vec<usize>, ParseError>>()?;
+            Ok(Record::new(springs, groups))
+        })
+        .collect()
+}
+
+// Total arrangements of every record after unfolding it `copies` times
+pub fn count_arrangements(records: &[Record], copies: usize) -> u128 {
+    records
+        .iter()
+        .map(|record| record.unfold(copies).arrangements())
+        .sum()
+}
+
+pub fn part_one(input: &str) -> Result<u128, ParseError> {
     let mut lines = input.split("\n").collect::<Vec<&str>>();
-    if lines[lines.len() - 1] == "" {
+    if lines[lines.len() - 1].is_empty() {
         lines.pop();
     }
-    todo!()
+    let records = process_input(lines)?;
+    Ok(count_arrangements(&records, 1))
 }
 
 #[cfg(test)]
@@ -17,6 +205,68 @@ mod tests {
 ????.#...#... 4,1,1
 ????.######..#####. 1,6,5
 ?###???????? 3,2,1";
-        assert_eq!(21, part_one(input_one));
+        assert_eq!(Ok(21), part_one(input_one));
+    }
+
+    #[test]
+    fn test_arrangements() {
+        let records = process_input(vec![
+            "???.### 1,1,3",
+            ".??..??...?##. 1,1,3",
+            "?###???????? 3,2,1",
+            "#.# 1,1",
+            "### 2",
+            "??? 4",
+        ])
+        .unwrap();
+        let counts: Vec<u128> = records.iter().map(Record::arrangements).collect();
+        assert_eq!(counts, vec![1, 4, 10, 1, 0, 0]);
+        assert_eq!(records[1].unfold(5).arrangements(), 16384);
+        assert_eq!(records[2].unfold(5).arrangements(), 506250);
+    }
+
+    #[test]
+    fn test_unfold() {
+        let record = process_input(vec![".# 1"]).unwrap().remove(0);
+        assert_eq!(
+            record.unfold(3),
+            process_input(vec![".#?.#?.# 1,1,1"]).unwrap().remove(0)
+        );
+        // Twenty single damaged springs among 219 springs is 200 choose 20,
+        // far past what a u64 holds
+        let record = process_input(vec!["?????????? 1"]).unwrap().remove(0);
+        assert_eq!(
+            record.unfold(20).arrangements(),
+            1613587787967350073386147640
+        );
+    }
+
+    #[test]
+    fn test_process_input_error() {
+        assert_eq!(
+            process_input(vec!["???.### 1,1,3", "??x 1"]),
+            Err(ParseError::UnexpectedToken {
+                line: 2,
+                column: 3,
+                token: String::from("x"),
+                expected: "., # or ?",
+            })
+        );
+        assert_eq!(
+            process_input(vec!["???.### 1,x,3"]),
+            Err(ParseError::InvalidNumber {
+                line: 1,
+                column: 11,
+                token: String::from("x"),
+            })
+        );
+        assert_eq!(
+            process_input(vec!["???.###"]),
+            Err(ParseError::MissingToken {
+                line: 1,
+                column: 8,
+                expected: "group sizes",
+            })
+        );
     }
 }
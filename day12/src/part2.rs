@@ -0,0 +1,26 @@
+use crate::part1::{count_arrangements, process_input};
+use common::parse::ParseError;
+
+pub fn part_two(input: &str) -> Result<u128, ParseError> {
+    let mut lines = input.split("\n").collect::<Vec<&str>>();
+    if lines[lines.len() - 1].is_empty() {
+        lines.pop();
+    }
+    let records = process_input(lines)?;
+    Ok(count_arrangements(&records, 5))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_part_two() {
+        let input = "???.### 1,1,3
+.??..??...?##. 1,1,3
+?#?#?#?#?#?#?#? 1,3,1,6
+????.#...#... 4,1,1
+????.######..#####. 1,6,5
+?###???????? 3,2,1";
+        assert_eq!(Ok(525152), part_two(input));
+    }
+}
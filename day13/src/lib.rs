@@ -1,2 +1,54 @@
+use common::{parse::ParseError, Answer, Solution};
+use std::{error::Error, fmt};
+
 pub mod part1;
 pub mod part2;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MirrorError {
+    Parse(ParseError),
+    // The island starting on `line` does not reflect anywhere with exactly
+    // `smudges` smudges
+    NoReflection { line: usize, smudges: usize },
+}
+
+impl fmt::Display for MirrorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MirrorError::Parse(err) => write!(f, "{}", err),
+            MirrorError::NoReflection { line, smudges } => write!(
+                f,
+                "the island on line {} has no reflection with {} smudges",
+                line, smudges
+            ),
+        }
+    }
+}
+
+impl Error for MirrorError {}
+
+impl From<ParseError> for MirrorError {
+    fn from(err: ParseError) -> Self {
+        MirrorError::Parse(err)
+    }
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    fn day(&self) -> u8 {
+        13
+    }
+
+    fn title(&self) -> &'static str {
+        "Point of Incidence"
+    }
+
+    fn part_one(&self, input: &str) -> Result<Answer, Box<dyn Error>> {
+        part1::part_one(input).map(Answer::from).map_err(Into::into)
+    }
+
+    fn part_two(&self, input: &str) -> Option<Result<Answer, Box<dyn Error>>> {
+        Some(part2::part_two(input).map(Answer::from).map_err(Into::into))
+    }
+}
@@ -1,126 +1,212 @@
+use crate::MirrorError;
+use common::parse::ParseError;
+use grid::{Grid, Position};
+
+// Rows and columns are packed into words of bits with bit i of word w set
+// when cell w * WORD_BITS + i along them is a #
+const WORD_BITS: usize = u64::BITS as usize;
+
 #[derive(Debug)]
 pub struct Island {
-    rows: Vec<String>,
-    cols: Vec<String>,
+    // 1-based line of the input the island starts on
+    line: usize,
+    rows: Vec<Vec<u64>>,
+    cols: Vec<Vec<u64>>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MirrorType {
     Row,
     Col,
 }
 
-impl Island {
-    fn new(rows: Vec<String>) -> Island {
-        // Determine the number of columns in the first row
-        let num_columns = rows[0].chars().count();
-
-        // Initialize a vector for each column
-        let mut cols = vec![String::new(); num_columns];
-
-        // Iterate over each row
-        for row in rows.clone() {
-            // Split the row into columns
-            for (i, value) in row.chars().enumerate() {
-                // Append the value to the corresponding column
-                if i < cols.len() {
-                    cols[i].push_str(&value.to_string());
-                }
-            }
+// A cell that has to be flipped for a reflection to be perfect, along with
+// the cell it is reflected onto. Flipping either one works.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Smudge {
+    pub position: Position,
+    pub mirrored: Position,
+}
+
+// A line of reflection with `index` rows above it or columns left of it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reflection {
+    pub mirror_type: MirrorType,
+    pub index: usize,
+    pub smudges: Vec<Smudge>,
+}
+
+impl Reflection {
+    // What the reflection adds to the puzzle answer
+    pub fn summary(&self) -> usize {
+        match self.mirror_type {
+            MirrorType::Row => self.index * 100,
+            MirrorType::Col => self.index,
         }
-        Island { rows, cols }
     }
 }
 
-pub fn check_fold(strings: &Vec<String>, left: usize, right: usize) -> bool {
-    let mut i = left;
-    let mut j = right;
-    loop {
-        let l = &strings[i];
-        let r = &strings[j];
-        if !l.eq(r) {
-            return false;
-        }
-        if i == 0 || j == strings.len() - 1 {
-            return true;
+impl Island {
+    fn new(line: usize, grid: &Grid<bool>) -> Island {
+        Island {
+            line,
+            rows: grid.rows().map(|row| pack(row.iter())).collect(),
+            cols: grid.columns().map(pack).collect(),
         }
-        i -= 1;
-        j += 1;
     }
-}
 
-pub fn check_for_mirrors(strings: &Vec<String>) -> Option<usize> {
-    let len = strings.len();
-    let mut right = 1;
+    // Every row and column reflection where exactly `smudges` cells differ
+    // from the cells they are reflected onto, rows first
+    pub fn reflections(&self, smudges: usize) -> Vec<Reflection> {
+        let rows = find_reflections(&self.rows, smudges)
+            .into_iter()
+            .map(|(index, differences)| Reflection {
+                mirror_type: MirrorType::Row,
+                index,
+                smudges: differences
+                    .into_iter()
+                    .map(|(y, mirrored_y, x)| Smudge {
+                        position: (x, y),
+                        mirrored: (x, mirrored_y),
+                    })
+                    .collect(),
+            });
+        let cols = find_reflections(&self.cols, smudges)
+            .into_iter()
+            .map(|(index, differences)| Reflection {
+                mirror_type: MirrorType::Col,
+                index,
+                smudges: differences
+                    .into_iter()
+                    .map(|(x, mirrored_x, y)| Smudge {
+                        position: (x, y),
+                        mirrored: (mirrored_x, y),
+                    })
+                    .collect(),
+            });
+        rows.chain(cols).collect()
+    }
 
-    for left in 0..len {
-        let l = &strings[left];
-        let r = &strings[right];
+    // Summary of the first reflection with exactly `smudges` smudges
+    pub fn summarize(&self, smudges: usize) -> Result<usize, MirrorError> {
+        self.reflections(smudges)
+            .first()
+            .map(Reflection::summary)
+            .ok_or(MirrorError::NoReflection {
+                line: self.line,
+                smudges,
+            })
+    }
+}
 
-        if l.eq(r) {
-            if check_fold(strings, left, right) {
-                return Some(left + 1);
-            }
+fn pack<'a>(cells: impl Iterator<Item = &'a bool>) -> Vec<u64> {
+    let mut words = Vec::new();
+    for (index, &rock) in cells.enumerate() {
+        if index % WORD_BITS == 0 {
+            words.push(0);
         }
-        right += 1;
-
-        if right >= len {
-            return None;
+        if rock {
+            words[index / WORD_BITS] |= 1 << (index % WORD_BITS);
         }
     }
-    None
+    words
 }
 
-impl Island {
-    fn find_mirror(&self, mirror_type: MirrorType) -> Option<usize> {
-        match mirror_type {
-            MirrorType::Row => check_for_mirrors(&self.rows),
-            MirrorType::Col => check_for_mirrors(&self.cols),
-        }
-    }
+// Words where two lines differ, with bits set where they do
+fn mismatches<'a>(a: &'a [u64], b: &'a [u64]) -> impl Iterator<Item = u64> + 'a {
+    a.iter().zip(b).map(|(a, b)| a ^ b)
+}
 
-    fn calculate_mirror_result(&self) -> usize {
-        let row_mirror = self.find_mirror(MirrorType::Row);
-        if row_mirror.is_none() {
-            return self.find_mirror(MirrorType::Col).unwrap();
-        }
-        return row_mirror.unwrap() * 100;
-    }
+// A mismatch between two lines as (line, mirrored line, offset within the
+// line)
+pub type Difference = (usize, usize, usize);
+
+// Every split between two of `lines` where the lines mirrored across it
+// differ in exactly `differences` places, along with the number of lines
+// before the split
+pub fn find_reflections(lines: &[Vec<u64>], differences: usize) -> Vec<(usize, Vec<Difference>)> {
+    (1..lines.len())
+        .filter_map(|split| {
+            let pairs = (0..split).rev().zip(split..lines.len());
+            let mut total = 0;
+            for (before, after) in pairs.clone() {
+                total += mismatches(&lines[before], &lines[after])
+                    .map(|mismatched| mismatched.count_ones() as usize)
+                    .sum::<usize>();
+                if total > differences {
+                    return None;
+                }
+            }
+            if total != differences {
+                return None;
+            }
+            // Only reflections that match need to know where the smudges are
+            let mut found = Vec::with_capacity(differences);
+            for (before, after) in pairs {
+                for (word, mut mismatched) in mismatches(&lines[before], &lines[after]).enumerate()
+                {
+                    while mismatched != 0 {
+                        let offset = word * WORD_BITS + mismatched.trailing_zeros() as usize;
+                        found.push((before, after, offset));
+                        mismatched &= mismatched - 1;
+                    }
+                }
+            }
+            Some((split, found))
+        })
+        .collect()
 }
 
-pub fn process_input(lines: Vec<&str>) -> Vec<Island> {
+pub fn process_input(lines: Vec<&str>) -> Result<Vec<Island>, ParseError> {
+    // Index of the first line of the current island within the whole input
+    let mut first_line = 0;
     lines
         .iter()
-        .map(|line| {
-            let island: Vec<String> = line
-                .split("\n")
-                .collect::<Vec<&str>>()
-                .iter()
-                .map(|l| l.to_string())
-                .collect();
-            let rows: Vec<String> = island.clone();
-            let result = Island::new(rows);
-            result
+        .map(|island| {
+            let grid = Grid::parse(island, "# or .", |c| match c {
+                '#' => Some(true),
+                '.' => Some(false),
+                _ => None,
+            })
+            .map_err(|err| match err {
+                ParseError::EmptyInput => ParseError::MissingToken {
+                    line: first_line + 1,
+                    column: 1,
+                    expected: "an island",
+                },
+                err => err.offset_lines(first_line),
+            })?;
+            let island = Island::new(first_line + 1, &grid);
+            // Islands are separated by a single blank line
+            first_line += grid.height() + 1;
+
+            Ok(island)
         })
         .collect()
 }
 
-pub fn part_one(input: &str) -> usize {
+// Adds up the summaries of every island once `smudges` cells are fixed
+pub fn summarize_notes(input: &str, smudges: usize) -> Result<usize, MirrorError> {
     let mut lines = input.split("\n\n").collect::<Vec<&str>>();
-    if lines[lines.len() - 1] == "" {
+    if lines[lines.len() - 1].is_empty() {
         lines.pop();
     }
-    process_input(lines)
+    process_input(lines)?
         .iter()
-        .map(|island| island.calculate_mirror_result()).sum()
+        .map(|island| island.summarize(smudges))
+        .sum()
+}
+
+pub fn part_one(input: &str) -> Result<usize, MirrorError> {
+    summarize_notes(input, 0)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-        #[test]
-        fn test_part_one() {
-            let input_one = "#.##..##.
+    #[test]
+    fn test_part_one() {
+        let input_one = "#.##..##.
 ..#.##.#.
 ##......#
 ##......#
@@ -135,6 +221,161 @@ mod tests {
 #####.##.
 ..##..###
 #....#..#";
-            assert_eq!(405, part_one(input_one));
-        }
+        assert_eq!(Ok(405), part_one(input_one));
+    }
+
+    #[test]
+    fn test_reflections() {
+        let islands = process_input(vec![
+            "#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.#.##.#.",
+            "#...##..#
+#....#..#
+..##..###
+#####.##.
+#####.##.
+..##..###
+#....#..#",
+        ])
+        .unwrap();
+        assert_eq!(
+            islands[0].reflections(0),
+            vec![Reflection {
+                mirror_type: MirrorType::Col,
+                index: 5,
+                smudges: vec![],
+            }]
+        );
+        assert_eq!(
+            islands[0].reflections(1),
+            vec![Reflection {
+                mirror_type: MirrorType::Row,
+                index: 3,
+                smudges: vec![Smudge {
+                    position: (0, 0),
+                    mirrored: (0, 5),
+                }],
+            }]
+        );
+        assert_eq!(
+            islands[1].reflections(1),
+            vec![Reflection {
+                mirror_type: MirrorType::Row,
+                index: 1,
+                smudges: vec![Smudge {
+                    position: (4, 0),
+                    mirrored: (4, 1),
+                }],
+            }]
+        );
+        // The smudge can be in the pair of rows or columns right next to the
+        // line and the same island can reflect both ways
+        let island = &process_input(vec!["#.\n..\n##"]).unwrap()[0];
+        assert_eq!(
+            island
+                .reflections(1)
+                .iter()
+                .map(|reflection| (reflection.mirror_type, reflection.index))
+                .collect::<Vec<(MirrorType, usize)>>(),
+            vec![(MirrorType::Row, 1), (MirrorType::Col, 1)]
+        );
+        assert!(island.reflections(3).is_empty());
+    }
+
+    #[test]
+    fn test_no_reflection() {
+        let input = "#.\n..\n\n#.\n.#";
+        assert_eq!(
+            summarize_notes(input, 2),
+            Err(MirrorError::NoReflection {
+                line: 1,
+                smudges: 2,
+            })
+        );
+        assert_eq!(
+            part_one(input).unwrap_err().to_string(),
+            "the island on line 1 has no reflection with 0 smudges"
+        );
+    }
+
+    #[test]
+    fn test_large_island() {
+        // 100 different rows mirrored between the middle two of 130 columns,
+        // too big for a single word either way
+        let mut rows: Vec<String> = (0..100_u32)
+            .map(|y| {
+                let half: String = (0..65)
+                    .map(|x| if y >> (x % 7) & 1 == 1 { '#' } else { '.' })
+                    .collect();
+                format!("{}{}", half, half.chars().rev().collect::<String>())
+            })
+            .collect();
+        let island = rows.join("\n");
+        let islands = process_input(vec![&island]).unwrap();
+        assert_eq!(
+            islands[0].reflections(0),
+            vec![Reflection {
+                mirror_type: MirrorType::Col,
+                index: 65,
+                smudges: vec![],
+            }]
+        );
+
+        // A smudge in the far corner lands in the second word of the column
+        rows[99].replace_range(129.., ".");
+        let island = rows.join("\n");
+        let islands = process_input(vec![&island]).unwrap();
+        assert!(islands[0].reflections(0).is_empty());
+        assert!(islands[0].reflections(1).contains(&Reflection {
+            mirror_type: MirrorType::Col,
+            index: 65,
+            smudges: vec![Smudge {
+                position: (0, 99),
+                mirrored: (129, 99),
+            }],
+        }));
+
+        // Same for a row reflection once the island is turned on its side
+        let flipped = transpose(&island);
+        let islands = process_input(vec![&flipped]).unwrap();
+        assert!(islands[0].reflections(1).contains(&Reflection {
+            mirror_type: MirrorType::Row,
+            index: 65,
+            smudges: vec![Smudge {
+                position: (99, 0),
+                mirrored: (99, 129),
+            }],
+        }));
+    }
+
+    fn transpose(island: &str) -> String {
+        let rows: Vec<Vec<char>> = island.lines().map(|row| row.chars().collect()).collect();
+        (0..rows[0].len())
+            .map(|x| rows.iter().map(|row| row[x]).collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_process_input_error() {
+        let input = "#.##..##.
+..#.##.#.
+
+#...##..#
+#....#.o#";
+        assert_eq!(
+            part_one(input),
+            Err(MirrorError::Parse(ParseError::UnexpectedToken {
+                line: 5,
+                column: 8,
+                token: String::from("o"),
+                expected: "# or .",
+            }))
+        );
+    }
 }
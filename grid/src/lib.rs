@@ -0,0 +1,370 @@
+use common::parse::{self, ParseError};
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+// Position of a cell as (x, y) which is (column, row) with (0, 0) in the top
+// left corner
+pub type Position = (usize, usize);
+
+// Offsets of the four orthogonal neighbors: north, east, south, west
+const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+// Offsets of all eight neighbors starting north and going clockwise
+const ALL_DIRECTIONS: [(isize, isize); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+// A rectangular grid of cells stored row by row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    // Panics when the number of cells does not match the dimensions
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(width * height, cells.len(), "cells do not fill the grid");
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    // None when the rows are not all the same length
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.len());
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        Some(Grid {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    // Parses one cell per character, `cell` returns None for characters that
+    // are not allowed and `expected` describes the allowed ones in errors.
+    // Every line has to be as long as the first.
+    pub fn parse<F>(input: &str, expected: &'static str, mut cell: F) -> Result<Self, ParseError>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+        for line in parse::lines(input) {
+            let mut row_width = 0;
+            for (index, c) in line.text.chars().enumerate() {
+                if width.is_some_and(|width| index >= width) {
+                    return Err(line.unexpected_char(index, c, "the end of the row"));
+                }
+                cells.push(cell(c).ok_or_else(|| line.unexpected_char(index, c, expected))?);
+                row_width += 1;
+            }
+            match width {
+                Some(width) if row_width < width => return Err(line.missing(expected)),
+                Some(_) => {}
+                None => width = Some(row_width),
+            }
+            height += 1;
+        }
+        match width {
+            Some(width) => Ok(Grid {
+                width,
+                height,
+                cells,
+            }),
+            None => Err(ParseError::EmptyInput),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (x, y): Position) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, position: Position) -> Option<&T> {
+        self.contains(position)
+            .then(|| &self.cells[position.1 * self.width + position.0])
+    }
+
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut T> {
+        if self.contains(position) {
+            Some(&mut self.cells[position.1 * self.width + position.0])
+        } else {
+            None
+        }
+    }
+
+    // The position `dx` columns and `dy` rows away, None when it is outside
+    // of the grid
+    pub fn offset(&self, (x, y): Position, dx: isize, dy: isize) -> Option<Position> {
+        let position = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+        self.contains(position).then_some(position)
+    }
+
+    // North, east, south and west neighbors that are inside the grid
+    pub fn neighbors4(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        ORTHOGONAL
+            .iter()
+            .filter_map(move |&(dx, dy)| self.offset(position, dx, dy))
+    }
+
+    // Orthogonal and diagonal neighbors that are inside the grid
+    pub fn neighbors8(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        ALL_DIRECTIONS
+            .iter()
+            .filter_map(move |&(dx, dy)| self.offset(position, dx, dy))
+    }
+
+    // Every position row by row
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    // Every cell along with its position row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        (y < self.height).then(|| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks panics on a size of 0 so use 1 for an empty grid which has
+        // no cells to chunk anyway
+        self.cells.chunks(self.width.max(1))
+    }
+
+    // Cells of a single column from top to bottom, empty when out of bounds
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        let cells = if x < self.width {
+            &self.cells[x..]
+        } else {
+            &[]
+        };
+        cells.iter().step_by(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    // Draws the grid with one character per cell and a newline after every row
+    pub fn render<F>(&self, f: F) -> String
+    where
+        F: Fn(&T) -> char,
+    {
+        let mut output = String::with_capacity((self.width + 1) * self.height);
+        for row in self.rows() {
+            output.extend(row.iter().map(&f));
+            output.push('\n');
+        }
+        output
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(width: usize, height: usize, value: T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    // Swaps rows and columns so (x, y) becomes (y, x)
+    pub fn transpose(&self) -> Grid<T> {
+        Grid {
+            width: self.height,
+            height: self.width,
+            cells: self.columns().flatten().cloned().collect(),
+        }
+    }
+
+    // Rotates a quarter turn so the first column becomes the first row read
+    // from the bottom up
+    pub fn rotate_clockwise(&self) -> Grid<T> {
+        Grid {
+            width: self.height,
+            height: self.width,
+            cells: self
+                .columns()
+                .flat_map(|column| column.collect::<Vec<&T>>().into_iter().rev())
+                .cloned()
+                .collect(),
+        }
+    }
+
+    // Rotates a quarter turn so the last column becomes the first row
+    pub fn rotate_counter_clockwise(&self) -> Grid<T> {
+        Grid {
+            width: self.height,
+            height: self.width,
+            cells: (0..self.width)
+                .rev()
+                .flat_map(|x| self.column(x))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+impl Grid<char> {
+    // Keeps every character as is
+    pub fn from_chars(input: &str) -> Result<Self, ParseError> {
+        Grid::parse(input, "a character", Some)
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Position) -> &T {
+        self.get(position).expect("position is outside of the grid")
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, position: Position) -> &mut T {
+        self.get_mut(position)
+            .expect("position is outside of the grid")
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let grid = Grid::parse("#.\n.#\n", "# or .", |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!((grid.width(), grid.height()), (2, 2));
+        assert!(grid[(0, 0)]);
+        assert_eq!(grid.get((1, 0)), Some(&false));
+        assert_eq!(grid.get((2, 0)), None);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Grid::parse("abc\nab", "a letter", Some),
+            Err(ParseError::MissingToken {
+                line: 2,
+                column: 3,
+                expected: "a letter",
+            })
+        );
+        assert_eq!(
+            Grid::parse("abc\nabcd", "a letter", Some),
+            Err(ParseError::UnexpectedToken {
+                line: 2,
+                column: 4,
+                token: String::from("d"),
+                expected: "the end of the row",
+            })
+        );
+        assert_eq!(
+            Grid::parse("ab\na?", "a letter", |c| c.is_alphabetic().then_some(c)),
+            Err(ParseError::UnexpectedToken {
+                line: 2,
+                column: 2,
+                token: String::from("?"),
+                expected: "a letter",
+            })
+        );
+        assert_eq!(Grid::from_chars(""), Err(ParseError::EmptyInput));
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = Grid::filled(3, 3, 0);
+        assert_eq!(
+            grid.neighbors4((0, 0)).collect::<Vec<Position>>(),
+            vec![(1, 0), (0, 1)]
+        );
+        assert_eq!(grid.neighbors4((1, 1)).count(), 4);
+        assert_eq!(
+            grid.neighbors8((2, 2)).collect::<Vec<Position>>(),
+            vec![(2, 1), (1, 2), (1, 1)]
+        );
+        assert_eq!(grid.neighbors8((1, 1)).count(), 8);
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = Grid::from_chars("abc\ndef").unwrap();
+        assert_eq!(grid.row(1), Some(&['d', 'e', 'f'][..]));
+        assert_eq!(
+            grid.columns()
+                .map(|column| column.collect::<String>())
+                .collect::<Vec<String>>(),
+            vec!["ad", "be", "cf"]
+        );
+        assert_eq!(grid.column(3).count(), 0);
+    }
+
+    #[test]
+    fn test_transform() {
+        let grid = Grid::from_chars("abc\ndef").unwrap();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf\n");
+        assert_eq!(grid.rotate_clockwise().to_string(), "da\neb\nfc\n");
+        assert_eq!(grid.rotate_counter_clockwise().to_string(), "cf\nbe\nad\n");
+        assert_eq!(
+            grid.map(|c| c.to_ascii_uppercase()).render(|&c| c),
+            "ABC\nDEF\n"
+        );
+        assert_eq!(Grid::from_rows(vec![vec!['a', 'b'], vec!['c']]), None);
+    }
+}